@@ -82,5 +82,11 @@ pub async fn main() -> SpeedrunApiResult<()> {
     let run: types::Run = endpoint.query_async(&client).await?;
     println!("{run:#?}");
 
+    // Follow the `game` link of the run.
+    if let Some(endpoint) = run.game_link() {
+        let game: types::Game = endpoint.query_async(&client).await?;
+        println!("{}", game.names.international);
+    }
+
     Ok(())
 }
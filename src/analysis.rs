@@ -0,0 +1,61 @@
+//! # Analysis
+//!
+//! Computations over runs that were already retrieved, for boards and
+//! statistics speedrun.com does not provide.
+//!
+//! A [`Leaderboard`] ranks a set of runs with the same semantics as the
+//! leaderboard endpoints, optionally with filters the API does not offer.
+//!
+//! ```rust ,no_run
+//! use futures::TryStreamExt;
+//! use speedrun_api::{
+//!     analysis::Leaderboard,
+//!     api::{runs::{Runs, RunStatus}, PagedEndpointExt},
+//!     types, SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//!
+//! let runs: Vec<types::Run> = Runs::builder()
+//!     .category("n2y5jzd3")
+//!     .status(RunStatus::Verified)
+//!     .build()?
+//!     .stream(&client)
+//!     .try_collect()
+//!     .await?;
+//!
+//! let board = Leaderboard::builder()
+//!     .category("n2y5jzd3")
+//!     .platform("n5e17e27")
+//!     .date("2010-01-01")
+//!     .build()?;
+//! for ranked in board.rank(runs) {
+//!     println!("{}. {}", ranked.place, ranked.run.times.primary);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`world_records`] reconstructs the world record progression of a board,
+//! either from runs that were already retrieved or live with
+//! [`world_record_history`] and [`world_record_history_async`].
+//! [`personal_bests`] (or [`pb_history`] and [`pb_history_async`]) does the
+//! same for the personal bests of a runner on every board they ran.
+//!
+//! A [`Snapshot`] of a leaderboard can be stored and later compared with a
+//! newer one, listing the changes in a [`LeaderboardDiff`].
+
+mod diff;
+mod history;
+mod leaderboard;
+
+pub use diff::{Change, DiffError, LeaderboardDiff, Snapshot, SnapshotEntry};
+pub use history::{
+    pb_history, pb_history_async, personal_bests, world_record_history, world_record_history_async,
+    world_records, HistoryError, PersonalBest, PersonalBestHistory, WorldRecord,
+};
+pub use leaderboard::{Leaderboard, LeaderboardBuilder, LeaderboardBuilderError};
+
+pub(crate) use leaderboard::time_for;
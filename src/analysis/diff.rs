@@ -0,0 +1,398 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{api::runs::RunId, types};
+
+use super::leaderboard::{players_key, time_for};
+
+/// Errors that occur when comparing snapshots.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DiffError {
+    /// The snapshots are of different leaderboards (see
+    /// [`Snapshot::same_board`]).
+    #[error("the snapshots are of different leaderboards")]
+    DifferentBoards,
+}
+
+/// The runs of a [`types::Leaderboard`] at one point in time, in a format that
+/// can be stored (e.g. as JSON) and compared with a later fetch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Link to the leaderboard on speedrun.com.
+    pub weblink: String,
+    /// `ID` of the game.
+    pub game: String,
+    /// `ID` of the category.
+    pub category: String,
+    /// `ID` of the level for individual-level leaderboards.
+    pub level: Option<String>,
+    /// Selected variable values.
+    pub values: BTreeMap<String, String>,
+    /// Runs on the leaderboard, in order.
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// A single run in a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// Place of the run.
+    pub place: i64,
+    /// `ID` of the run.
+    pub run: RunId<'static>,
+    /// Players of the run, as `user:<ID>` or `guest:<name>`.
+    pub players: Vec<String>,
+    /// Time of the run in seconds, using the timing method of the leaderboard.
+    pub time: f64,
+    /// Date the run was done.
+    pub date: Option<String>,
+}
+
+/// A single change between two snapshots of a leaderboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", tag = "change")]
+pub enum Change {
+    /// A run was added to the leaderboard.
+    NewRun {
+        /// The new run.
+        entry: SnapshotEntry,
+    },
+    /// A run is no longer on the leaderboard (e.g. it was rejected or
+    /// deleted). Runs replaced by a faster run of the same players are
+    /// reported with that run instead.
+    RemovedRun {
+        /// The removed run.
+        entry: SnapshotEntry,
+    },
+    /// A run moved to a different place.
+    PlaceChanged {
+        /// `ID` of the run.
+        run: RunId<'static>,
+        /// Previous place.
+        from: i64,
+        /// Current place.
+        to: i64,
+    },
+    /// The time of a run changed (e.g. it was retimed by a moderator).
+    TimeChanged {
+        /// `ID` of the run.
+        run: RunId<'static>,
+        /// Previous time in seconds.
+        from: f64,
+        /// Current time in seconds.
+        to: f64,
+    },
+    /// A new run took first place. It is not reported as
+    /// [`Change::ImprovedPersonalBest`] as well.
+    NewWorldRecord {
+        /// The record run.
+        entry: SnapshotEntry,
+        /// The previous record, if there was one.
+        previous: Option<SnapshotEntry>,
+        /// The run of the same players it replaced, if there was one.
+        replaced: Option<SnapshotEntry>,
+    },
+    /// Players already on the leaderboard submitted a faster run.
+    ImprovedPersonalBest {
+        /// The new run.
+        entry: SnapshotEntry,
+        /// The run it replaced.
+        previous: SnapshotEntry,
+        /// Seconds saved.
+        saved: f64,
+    },
+}
+
+/// Changes between two snapshots of the same leaderboard.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LeaderboardDiff {
+    /// Changes, ordered by the current place of the runs involved, followed by
+    /// removed runs.
+    pub changes: Vec<Change>,
+}
+
+impl Snapshot {
+    /// Take a snapshot of `leaderboard`.
+    pub fn new(leaderboard: &types::Leaderboard<'_>) -> Self {
+        Self {
+            weblink: leaderboard.weblink.clone(),
+            game: leaderboard.game.to_string(),
+            category: leaderboard.category.to_string(),
+            level: leaderboard.level.as_ref().map(ToString::to_string),
+            values: leaderboard
+                .values
+                .iter()
+                .map(|(var, val)| (var.to_string(), val.clone()))
+                .collect(),
+            entries: leaderboard
+                .runs
+                .iter()
+                .map(|ranked| SnapshotEntry {
+                    place: ranked.place,
+                    run: RunId::new(ranked.run.id.to_string()),
+                    players: players_key(&ranked.run),
+                    time: time_for(&ranked.run, leaderboard.timing.as_ref())
+                        .unwrap_or(ranked.run.times.primary_t),
+                    date: ranked.run.date.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// If both snapshots are of the same leaderboard.
+    pub fn same_board(&self, other: &Snapshot) -> bool {
+        self.game == other.game
+            && self.category == other.category
+            && self.level == other.level
+            && self.values == other.values
+    }
+}
+
+impl From<&types::Leaderboard<'_>> for Snapshot {
+    fn from(value: &types::Leaderboard<'_>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl LeaderboardDiff {
+    /// Compute the changes from `old` to `new`.
+    ///
+    /// Fails if the snapshots are not of the same leaderboard.
+    pub fn between(old: &Snapshot, new: &Snapshot) -> Result<Self, DiffError> {
+        if !old.same_board(new) {
+            return Err(DiffError::DifferentBoards);
+        }
+
+        let old_runs: HashMap<&RunId<'static>, &SnapshotEntry> = old
+            .entries
+            .iter()
+            .map(|entry| (&entry.run, entry))
+            .collect();
+        let old_players: HashMap<&[String], &SnapshotEntry> = old
+            .entries
+            .iter()
+            .map(|entry| (entry.players.as_slice(), entry))
+            .collect();
+        let new_runs: HashMap<&RunId<'static>, &SnapshotEntry> = new
+            .entries
+            .iter()
+            .map(|entry| (&entry.run, entry))
+            .collect();
+        let new_players: HashMap<&[String], &SnapshotEntry> = new
+            .entries
+            .iter()
+            .map(|entry| (entry.players.as_slice(), entry))
+            .collect();
+        let old_record = old.entries.first();
+
+        let mut changes = Vec::new();
+        for entry in &new.entries {
+            if let Some(previous) = old_runs.get(&entry.run) {
+                if previous.time != entry.time {
+                    changes.push(Change::TimeChanged {
+                        run: entry.run.clone(),
+                        from: previous.time,
+                        to: entry.time,
+                    });
+                }
+                if previous.place != entry.place {
+                    changes.push(Change::PlaceChanged {
+                        run: entry.run.clone(),
+                        from: previous.place,
+                        to: entry.place,
+                    });
+                }
+                continue;
+            }
+
+            let replaced = old_players
+                .get(entry.players.as_slice())
+                .filter(|previous| {
+                    !new_runs.contains_key(&previous.run) && entry.time < previous.time
+                });
+            let record =
+                entry.place == 1 && old_record.is_none_or(|record| record.time > entry.time);
+            changes.push(match replaced {
+                _ if record => Change::NewWorldRecord {
+                    entry: entry.clone(),
+                    previous: old_record.cloned(),
+                    replaced: replaced.map(|previous| (*previous).clone()),
+                },
+                Some(previous) => Change::ImprovedPersonalBest {
+                    entry: entry.clone(),
+                    previous: (*previous).clone(),
+                    saved: previous.time - entry.time,
+                },
+                None => Change::NewRun {
+                    entry: entry.clone(),
+                },
+            });
+        }
+
+        for entry in &old.entries {
+            let improved = new_players
+                .get(entry.players.as_slice())
+                .is_some_and(|current| {
+                    !old_runs.contains_key(&current.run) && current.time < entry.time
+                });
+            if !new_runs.contains_key(&entry.run) && !improved {
+                changes.push(Change::RemovedRun {
+                    entry: entry.clone(),
+                });
+            }
+        }
+
+        Ok(Self { changes })
+    }
+
+    /// If nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// New world records.
+    pub fn world_records(&self) -> impl Iterator<Item = &SnapshotEntry> {
+        self.changes.iter().filter_map(|change| match change {
+            Change::NewWorldRecord { entry, .. } => Some(entry),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn entry(place: i64, run: &str, player: &str, time: f64) -> SnapshotEntry {
+        SnapshotEntry {
+            place,
+            run: RunId::new(run.to_owned()),
+            players: vec![format!("user:{player}")],
+            time,
+            date: None,
+        }
+    }
+
+    fn snapshot(entries: Vec<SnapshotEntry>) -> Snapshot {
+        Snapshot {
+            weblink: String::new(),
+            game: "g".into(),
+            category: "c".into(),
+            level: None,
+            values: BTreeMap::new(),
+            entries,
+        }
+    }
+
+    #[test]
+    fn diff_snapshots() {
+        let old = snapshot(vec![
+            entry(1, "a1", "a", 100.0),
+            entry(2, "b1", "b", 110.0),
+            entry(3, "c1", "c", 120.0),
+        ]);
+        let new = snapshot(vec![
+            entry(1, "b2", "b", 95.0),
+            entry(2, "a1", "a", 100.0),
+            entry(3, "d1", "d", 130.0),
+        ]);
+
+        let diff = LeaderboardDiff::between(&old, &new).unwrap();
+        assert_eq!(
+            diff.changes,
+            [
+                Change::NewWorldRecord {
+                    entry: entry(1, "b2", "b", 95.0),
+                    previous: Some(entry(1, "a1", "a", 100.0)),
+                    replaced: Some(entry(2, "b1", "b", 110.0)),
+                },
+                Change::PlaceChanged {
+                    run: RunId::new("a1"),
+                    from: 1,
+                    to: 2,
+                },
+                Change::NewRun {
+                    entry: entry(3, "d1", "d", 130.0),
+                },
+                Change::RemovedRun {
+                    entry: entry(3, "c1", "c", 120.0),
+                },
+            ]
+        );
+        assert!(LeaderboardDiff::between(&new, &new).unwrap().is_empty());
+    }
+
+    #[test]
+    fn improved_personal_best() {
+        let old = snapshot(vec![entry(1, "a1", "a", 100.0), entry(2, "b1", "b", 110.0)]);
+        let new = snapshot(vec![entry(1, "a1", "a", 100.0), entry(2, "b2", "b", 105.0)]);
+
+        assert_eq!(
+            LeaderboardDiff::between(&old, &new).unwrap().changes,
+            [Change::ImprovedPersonalBest {
+                entry: entry(2, "b2", "b", 105.0),
+                previous: entry(2, "b1", "b", 110.0),
+                saved: 5.0,
+            }]
+        );
+    }
+
+    #[test]
+    fn slower_replacement() {
+        let old = snapshot(vec![entry(1, "a1", "a", 100.0), entry(2, "b1", "b", 110.0)]);
+        let new = snapshot(vec![entry(1, "a1", "a", 100.0), entry(2, "b2", "b", 115.0)]);
+
+        assert_eq!(
+            LeaderboardDiff::between(&old, &new).unwrap().changes,
+            [
+                Change::NewRun {
+                    entry: entry(2, "b2", "b", 115.0),
+                },
+                Change::RemovedRun {
+                    entry: entry(2, "b1", "b", 110.0),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn retimed_run() {
+        let old = snapshot(vec![entry(1, "a1", "a", 100.0), entry(2, "b1", "b", 110.0)]);
+        let new = snapshot(vec![entry(1, "b1", "b", 90.0), entry(2, "a1", "a", 100.0)]);
+
+        assert_eq!(
+            LeaderboardDiff::between(&old, &new).unwrap().changes,
+            [
+                Change::TimeChanged {
+                    run: RunId::new("b1"),
+                    from: 110.0,
+                    to: 90.0,
+                },
+                Change::PlaceChanged {
+                    run: RunId::new("b1"),
+                    from: 2,
+                    to: 1,
+                },
+                Change::PlaceChanged {
+                    run: RunId::new("a1"),
+                    from: 1,
+                    to: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn different_boards() {
+        let old = snapshot(Vec::new());
+        let mut new = snapshot(Vec::new());
+        new.level = Some("l".into());
+
+        assert!(matches!(
+            LeaderboardDiff::between(&old, &new),
+            Err(DiffError::DifferentBoards)
+        ));
+    }
+}
@@ -0,0 +1,497 @@
+use std::collections::{BTreeMap, HashMap};
+
+use futures::TryStreamExt;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::{CategoryId, CategoryVariables},
+        games::GameId,
+        levels::LevelId,
+        runs::{RunStatus, Runs, RunsSorting},
+        users::UserId,
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, PagedEndpointExt, Query,
+    },
+    types,
+};
+
+use super::{
+    leaderboard::{players_key, time_for},
+    Leaderboard,
+};
+
+/// Errors that occur when reconstructing the history of a board.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum HistoryError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The board has no category to retrieve runs for.
+    #[error("the leaderboard has no category")]
+    MissingCategory,
+}
+
+/// A run that was the world record of a board.
+#[derive(Debug, Clone)]
+pub struct WorldRecord<'a> {
+    /// The record run.
+    pub run: types::Run<'a>,
+    /// Time of the run in seconds, using the timing method of the board.
+    pub time: f64,
+    /// Seconds saved over the previous record, `None` for the first record.
+    pub margin: Option<f64>,
+    /// Number of days until the record was beaten, `None` while it stands.
+    pub stood: Option<i64>,
+}
+
+impl<'a> WorldRecord<'a> {
+    /// Date the record was set.
+    pub fn date(&self) -> &str {
+        self.run.date.as_deref().unwrap_or_default()
+    }
+
+    /// Players that set the record.
+    pub fn players(&self) -> &[types::Player<'a>] {
+        &self.run.players
+    }
+}
+
+/// Reconstructs the chronological sequence of world records on `board` from
+/// `runs`.
+///
+/// Runs without a date are ignored. A run tying the current record does not
+/// replace it.
+pub fn world_records<'r, I>(board: &Leaderboard<'_>, runs: I) -> Vec<WorldRecord<'r>>
+where
+    I: IntoIterator<Item = types::Run<'r>>,
+{
+    let mut runs: Vec<(f64, types::Run<'r>)> = runs
+        .into_iter()
+        .filter(|run| run.date.is_some() && board.matches(run))
+        .filter_map(|run| Some((time_for(&run, board.timing())?, run)))
+        .collect();
+    runs.sort_by(|(_, a), (_, b)| {
+        a.date
+            .cmp(&b.date)
+            .then_with(|| a.submitted.cmp(&b.submitted))
+    });
+
+    let mut records: Vec<WorldRecord<'r>> = Vec::new();
+    for (time, run) in runs {
+        let date = run.date.as_deref().unwrap_or_default();
+        let margin = match records.last_mut() {
+            Some(record) if time >= record.time => continue,
+            Some(record) => {
+                record.stood = days_between(record.date(), date);
+                Some(record.time - time)
+            }
+            None => None,
+        };
+        records.push(WorldRecord {
+            run,
+            time,
+            margin,
+            stood: None,
+        });
+    }
+    records
+}
+
+/// Retrieves every verified run of the category (and level) of `board` and
+/// reconstructs its world record history.
+pub fn world_record_history<C>(
+    client: &C,
+    board: &Leaderboard<'_>,
+) -> Result<Vec<WorldRecord<'static>>, HistoryError<C::Error>>
+where
+    C: Client,
+{
+    let endpoint = board.runs_endpoint().ok_or(HistoryError::MissingCategory)?;
+    let mut runs = Vec::new();
+    for run in endpoint.iter::<types::Run<'static>, _>(client) {
+        runs.push(run?);
+    }
+    Ok(world_records(board, runs))
+}
+
+/// Retrieves every verified run of the category (and level) of `board`
+/// asynchronously and reconstructs its world record history.
+pub async fn world_record_history_async<C>(
+    client: &C,
+    board: &Leaderboard<'_>,
+) -> Result<Vec<WorldRecord<'static>>, HistoryError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    let endpoint = board.runs_endpoint().ok_or(HistoryError::MissingCategory)?;
+    let runs: Vec<types::Run<'static>> = endpoint.stream(client).try_collect().await?;
+    Ok(world_records(board, runs))
+}
+
+/// A run that improved the personal best of its players.
+#[derive(Debug, Clone)]
+pub struct PersonalBest<'a> {
+    /// The improving run.
+    pub run: types::Run<'a>,
+    /// Primary time of the run in seconds.
+    pub time: f64,
+    /// Seconds saved over the previous personal best, `None` for the first
+    /// run.
+    pub saved: Option<f64>,
+    /// Place the run reached on the board at the date it was done, if the
+    /// runs of the board were provided.
+    pub place: Option<i64>,
+}
+
+/// Personal best progression on a single board.
+#[derive(Debug, Clone)]
+pub struct PersonalBestHistory<'a> {
+    /// Category of the board.
+    pub category: CategoryId<'a>,
+    /// Level of the board for individual-level runs.
+    pub level: Option<LevelId<'a>>,
+    /// Subcategory values selecting the board.
+    pub values: Vec<(VariableId<'a>, ValueId<'a>)>,
+    /// Chronological list of improvements.
+    pub improvements: Vec<PersonalBest<'a>>,
+}
+
+/// Groups `runs` of a runner by board and lists each board's personal best
+/// improvements in chronological order.
+///
+/// Boards are identified by category, level and the values of the variables
+/// in `subcategories`. Only verified runs with a date are considered. When
+/// `board_runs` contains all verified runs of the boards, each improvement
+/// includes the place it reached at the time.
+pub fn personal_bests<'r, I>(
+    runs: I,
+    subcategories: &[VariableId<'_>],
+    board_runs: &[types::Run<'r>],
+) -> Vec<PersonalBestHistory<'r>>
+where
+    I: IntoIterator<Item = types::Run<'r>>,
+{
+    let mut boards: BTreeMap<_, Vec<(f64, types::Run<'r>)>> = BTreeMap::new();
+    for run in runs {
+        if run.date.is_none() || !matches!(run.status, types::Status::Verified { .. }) {
+            continue;
+        }
+        let Some(time) = time_for(&run, None) else {
+            continue;
+        };
+        let values: Vec<(String, String)> = subcategories
+            .iter()
+            .filter_map(|var| Some((var.to_string(), run.values.get(var)?.clone())))
+            .collect();
+        let key = (
+            run.category.to_string(),
+            run.level.as_ref().map(ToString::to_string),
+            values,
+        );
+        boards.entry(key).or_default().push((time, run));
+    }
+
+    boards
+        .into_values()
+        .map(|mut runs| {
+            runs.sort_by(|(_, a), (_, b)| {
+                a.date
+                    .cmp(&b.date)
+                    .then_with(|| a.submitted.cmp(&b.submitted))
+            });
+            let first = &runs[0].1;
+            let mut history = PersonalBestHistory {
+                category: first.category.clone(),
+                level: first.level.clone(),
+                values: subcategories
+                    .iter()
+                    .filter_map(|var| {
+                        let value = first.values.get(var)?;
+                        Some((
+                            VariableId::new(var.to_string()),
+                            ValueId::new(value.clone()),
+                        ))
+                    })
+                    .collect(),
+                improvements: Vec::new(),
+            };
+            for (time, run) in runs {
+                let saved = match history.improvements.last() {
+                    Some(best) if time >= best.time => continue,
+                    Some(best) => Some(best.time - time),
+                    None => None,
+                };
+                history.improvements.push(PersonalBest {
+                    run,
+                    time,
+                    saved,
+                    place: None,
+                });
+            }
+            if !board_runs.is_empty() {
+                places_at_the_time(&mut history, board_runs);
+            }
+            history
+        })
+        .collect()
+}
+
+/// Retrieves the runs of `user` in `category` of `game` and lists the personal
+/// best improvements on each board.
+///
+/// To determine the place of each improvement at the time, every verified run
+/// of the category is retrieved as well.
+pub fn pb_history<'a, U, G, Cat, C>(
+    client: &C,
+    user: U,
+    game: G,
+    category: Cat,
+) -> Result<Vec<PersonalBestHistory<'static>>, HistoryError<C::Error>>
+where
+    U: Into<UserId<'a>>,
+    G: Into<GameId<'a>>,
+    Cat: Into<CategoryId<'a>>,
+    C: Client,
+{
+    let (user_runs, board_runs, variables) =
+        pb_endpoints(user.into(), game.into(), category.into());
+    let mut runs = Vec::new();
+    for run in user_runs.iter::<types::Run<'static>, _>(client) {
+        runs.push(run?);
+    }
+    let mut all_runs = Vec::new();
+    for run in board_runs.iter::<types::Run<'static>, _>(client) {
+        all_runs.push(run?);
+    }
+    let variables: Vec<types::Variable<'static>> = variables.query(client)?;
+    Ok(personal_bests(runs, &subcategories(&variables), &all_runs))
+}
+
+/// Retrieves the runs of `user` in `category` of `game` asynchronously and
+/// lists the personal best improvements on each board.
+///
+/// To determine the place of each improvement at the time, every verified run
+/// of the category is retrieved as well.
+pub async fn pb_history_async<'a, U, G, Cat, C>(
+    client: &C,
+    user: U,
+    game: G,
+    category: Cat,
+) -> Result<Vec<PersonalBestHistory<'static>>, HistoryError<C::Error>>
+where
+    U: Into<UserId<'a>>,
+    G: Into<GameId<'a>>,
+    Cat: Into<CategoryId<'a>>,
+    C: AsyncClient + Sync,
+{
+    let (user_runs, board_runs, variables) =
+        pb_endpoints(user.into(), game.into(), category.into());
+    let runs: Vec<types::Run<'static>> = user_runs.stream(client).try_collect().await?;
+    let all_runs: Vec<types::Run<'static>> = board_runs.stream(client).try_collect().await?;
+    let variables: Vec<types::Variable<'static>> = variables.query_async(client).await?;
+    Ok(personal_bests(runs, &subcategories(&variables), &all_runs))
+}
+
+fn pb_endpoints<'a>(
+    user: UserId<'a>,
+    game: GameId<'a>,
+    category: CategoryId<'a>,
+) -> (Runs<'a>, Runs<'a>, CategoryVariables<'a>) {
+    let user_runs = Runs::builder()
+        .user(user)
+        .game(game)
+        .category(category.clone())
+        .status(RunStatus::Verified)
+        .build()
+        .expect("all required fields are set");
+    let board_runs = Runs::builder()
+        .category(category.clone())
+        .status(RunStatus::Verified)
+        .orderby(RunsSorting::Date)
+        .direction(Direction::Asc)
+        .build()
+        .expect("all required fields are set");
+    let variables = CategoryVariables::builder()
+        .id(category)
+        .build()
+        .expect("all required fields are set");
+    (user_runs, board_runs, variables)
+}
+
+fn subcategories(variables: &[types::Variable<'static>]) -> Vec<VariableId<'static>> {
+    variables
+        .iter()
+        .filter(|variable| variable.is_subcategory)
+        .map(|variable| variable.id.clone())
+        .collect()
+}
+
+/// Set the place of each improvement of `history` on its board, counting only
+/// the `board_runs` done up to the same date.
+///
+/// The runs are replayed in chronological order once, keeping the best time of
+/// each runner.
+fn places_at_the_time(history: &mut PersonalBestHistory<'_>, board_runs: &[types::Run<'_>]) {
+    let mut builder = Leaderboard::builder();
+    builder
+        .category(history.category.clone())
+        .variables(history.values.iter().cloned());
+    if let Some(level) = &history.level {
+        builder.level(level.clone());
+    }
+    let board = builder.build().expect("all required fields are set");
+
+    let mut runs: Vec<(&str, f64, Vec<String>)> = board_runs
+        .iter()
+        .filter(|run| board.matches(run))
+        .filter_map(|run| Some((run.date.as_deref()?, time_for(run, None)?, players_key(run))))
+        .collect();
+    runs.sort_by_key(|(date, ..)| *date);
+
+    let mut best: HashMap<Vec<String>, f64> = HashMap::new();
+    let mut runs = runs.into_iter().peekable();
+    for improvement in &mut history.improvements {
+        let Some(date) = improvement.run.date.as_deref() else {
+            continue;
+        };
+        while let Some((_, time, players)) = runs.next_if(|(run_date, ..)| *run_date <= date) {
+            let best = best.entry(players).or_insert(time);
+            *best = best.min(time);
+        }
+        let players = players_key(&improvement.run);
+        let faster = best
+            .iter()
+            .filter(|(key, time)| **key != players && **time < improvement.time)
+            .count();
+        improvement.place = Some(faster as i64 + 1);
+    }
+}
+
+/// Number of days from `from` to `to` (ISO 8601 dates, optionally with a
+/// time that is ignored).
+pub(crate) fn days_between(from: &str, to: &str) -> Option<i64> {
+    Some(days(to)? - days(from)?)
+}
+
+/// Days since 1970-01-01 of an ISO 8601 date.
+fn days(date: &str) -> Option<i64> {
+    let mut parts = date.get(..10)?.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: i64 = parts.next()?.parse().ok()?;
+    let day: i64 = parts.next()?.parse().ok()?;
+
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146_097 + day_of_era - 719_468)
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        fixtures::{self, run},
+        mock::{self, MockClient},
+    };
+
+    #[test]
+    fn world_record_progression() {
+        let runs = vec![
+            run("c", "b", 95.0, "2020-03-01"),
+            run("a", "a", 100.0, "2020-01-01"),
+            run("b", "b", 100.0, "2020-02-01"),
+            run("d", "a", 90.0, "2021-03-01"),
+        ];
+        let board = Leaderboard::builder().build().unwrap();
+        let records: Vec<_> = world_records(&board, runs)
+            .into_iter()
+            .map(|record| (record.run.id.to_string(), record.margin, record.stood))
+            .collect();
+        assert_eq!(
+            records,
+            [
+                ("a".to_owned(), None, Some(60)),
+                ("c".to_owned(), Some(5.0), Some(365)),
+                ("d".to_owned(), Some(5.0), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn personal_best_progression() {
+        let runs = vec![
+            run("a1", "a", 100.0, "2020-01-01"),
+            run("a2", "a", 105.0, "2020-02-01"),
+            run("a3", "a", 90.0, "2021-01-01"),
+        ];
+        let mut board_runs = runs.clone();
+        board_runs.push(run("b1", "b", 85.0, "2020-06-01"));
+        board_runs.push(run("c1", "c", 90.0, "2020-12-01"));
+        board_runs.push(run("c2", "c", 80.0, "2021-02-01"));
+
+        let history = personal_bests(runs, &[], &board_runs);
+        assert_eq!(history.len(), 1);
+        let improvements: Vec<_> = history[0]
+            .improvements
+            .iter()
+            .map(|pb| (pb.run.id.to_string(), pb.saved, pb.place))
+            .collect();
+        assert_eq!(
+            improvements,
+            [
+                ("a1".to_owned(), None, Some(1)),
+                ("a3".to_owned(), Some(10.0), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn pb_history_with_places() {
+        let user_runs: Vec<Value> = vec![
+            fixtures::run_json("a1", "a", 100.0, "2020-01-01"),
+            fixtures::run_json("a2", "a", 90.0, "2021-01-01"),
+        ];
+        let mut board_runs = user_runs.clone();
+        board_runs.push(fixtures::run_json("b1", "b", 95.0, "2020-06-01"));
+        board_runs.push(fixtures::run_json("c1", "c", 80.0, "2020-12-01"));
+        let client = MockClient::new(move |request| match request.path() {
+            "runs" if request.query("user").is_some() => Some(mock::page(request, &user_runs)),
+            "runs" => Some(mock::page(request, &board_runs)),
+            "categories/c/variables" => Some(json!([])),
+            _ => None,
+        });
+
+        let history = pb_history(&client, "a", "g", "c").unwrap();
+        let places: Vec<_> = history[0]
+            .improvements
+            .iter()
+            .map(|pb| (pb.run.id.to_string(), pb.place))
+            .collect();
+        assert_eq!(
+            places,
+            [("a1".to_owned(), Some(1)), ("a2".to_owned(), Some(2))]
+        );
+        let board_request = client
+            .requests()
+            .into_iter()
+            .find(|request| request.path() == "runs" && request.query("user").is_none())
+            .unwrap();
+        assert_eq!(board_request.query("category").as_deref(), Some("c"));
+        assert_eq!(board_request.query("status").as_deref(), Some("verified"));
+    }
+
+    #[test]
+    fn days_between_dates() {
+        assert_eq!(days("1970-01-01"), Some(0));
+        assert_eq!(days_between("2000-02-28", "2000-03-01T12:00:00Z"), Some(2));
+    }
+}
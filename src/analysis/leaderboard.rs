@@ -0,0 +1,274 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        levels::LevelId,
+        platforms::PlatformId,
+        regions::RegionId,
+        runs::{RunStatus, Runs, RunsSorting},
+        variables::{ValueId, VariableId},
+        Direction,
+    },
+    types::{self, RankedRun, Status, TimingMethod},
+};
+
+/// Ranks a set of runs the way the leaderboard endpoints do.
+///
+/// Only verified runs are ranked, and only the fastest run of each set of
+/// players (runs made obsolete by a faster run are excluded). Runs with the
+/// same time share a place; among them the run done first is listed first.
+///
+/// With the same filters, the result matches what [`FullGameLeaderboard`] and
+/// [`IndividualLevelLeaderboard`] return for the same runs.
+///
+/// [`FullGameLeaderboard`]: crate::api::leaderboards::FullGameLeaderboard
+/// [`IndividualLevelLeaderboard`]: crate::api::leaderboards::IndividualLevelLeaderboard
+#[derive(Debug, Default, Builder, Clone)]
+#[builder(default, setter(into, strip_option))]
+pub struct Leaderboard<'a> {
+    #[doc = r"Only rank runs of `category`. When set, `level` selects between full-game runs (unset) and runs of a level."]
+    category: Option<CategoryId<'a>>,
+    #[doc = r"Only rank runs of `level` (requires `category`)."]
+    level: Option<LevelId<'a>>,
+    #[doc = r"Only return `top` places."]
+    top: Option<i64>,
+    #[doc = r"Only rank runs done on `platform`."]
+    platform: Option<PlatformId<'a>>,
+    #[doc = r"Only rank runs done in `region`."]
+    region: Option<RegionId<'a>>,
+    #[doc = r"When unset, real devices and emulator results are ranked. When `true` only emulator runs are ranked, otherwise only real devices are ranked."]
+    emulators: Option<bool>,
+    #[doc = r"When `true` only runs with videos are ranked. (default: `false`)"]
+    video_only: bool,
+    #[doc = r"What [`TimingMethod`] to use to rank runs (default: the primary time of each run)."]
+    timing: Option<TimingMethod>,
+    #[doc = r"Only rank runs done on or before this date. [ISO 8601 date string](https://en.wikipedia.org/wiki/ISO_8601#Dates)."]
+    date: Option<String>,
+    #[builder(setter(name = "_variables"), private)]
+    variables: HashMap<VariableId<'a>, ValueId<'a>>,
+}
+
+impl<'a> Leaderboard<'a> {
+    /// Create a builder for an offline leaderboard.
+    pub fn builder() -> LeaderboardBuilder<'a> {
+        LeaderboardBuilder::default()
+    }
+
+    /// Rank `runs`, returning the runs on the board in order.
+    pub fn rank<'r, I>(&self, runs: I) -> Vec<RankedRun<'r>>
+    where
+        I: IntoIterator<Item = types::Run<'r>>,
+    {
+        let mut runs: Vec<(f64, types::Run<'r>)> = runs
+            .into_iter()
+            .filter(|run| self.matches(run))
+            .filter_map(|run| Some((time_for(&run, self.timing.as_ref())?, run)))
+            .collect();
+        runs.sort_by(|(a, run_a), (b, run_b)| {
+            a.total_cmp(b)
+                .then_with(|| run_a.date.cmp(&run_b.date))
+                .then_with(|| run_a.submitted.cmp(&run_b.submitted))
+        });
+
+        let mut seen = HashSet::new();
+        let mut ranked: Vec<RankedRun<'r>> = Vec::new();
+        let mut previous = None;
+        for (time, run) in runs {
+            if !seen.insert(players_key(&run)) {
+                continue;
+            }
+            let place = match (previous, ranked.last()) {
+                (Some(previous), Some(last)) if previous == time => last.place,
+                _ => ranked.len() as i64 + 1,
+            };
+            if self.top.is_some_and(|top| place > top) {
+                break;
+            }
+            previous = Some(time);
+            ranked.push(RankedRun { place, run });
+        }
+        ranked
+    }
+
+    /// If `run` would be listed on this board (ignoring faster runs by the
+    /// same players).
+    pub fn matches(&self, run: &types::Run<'_>) -> bool {
+        if !matches!(run.status, Status::Verified { .. }) {
+            return false;
+        }
+        if let Some(category) = &self.category {
+            if run.category != *category || run.level != self.level {
+                return false;
+            }
+        }
+        if self.platform.is_some() && run.system.platform != self.platform {
+            return false;
+        }
+        if self.region.is_some() && run.system.region != self.region {
+            return false;
+        }
+        if self
+            .emulators
+            .is_some_and(|emulators| emulators != run.system.emulated)
+        {
+            return false;
+        }
+        if self.video_only
+            && run
+                .videos
+                .as_ref()
+                .is_none_or(|videos| videos.links.is_empty())
+        {
+            return false;
+        }
+        if let Some(date) = &self.date {
+            if run
+                .date
+                .as_deref()
+                .is_none_or(|run_date| run_date > date.as_str())
+            {
+                return false;
+            }
+        }
+        self.variables.iter().all(|(variable, value)| {
+            run.values
+                .get(variable)
+                .is_some_and(|val| *val == value.to_string())
+        })
+    }
+
+    pub(crate) fn timing(&self) -> Option<&TimingMethod> {
+        self.timing.as_ref()
+    }
+
+    /// Endpoint retrieving every verified run that may be on this board, from
+    /// oldest to newest. Returns `None` if no category is set.
+    pub(crate) fn runs_endpoint(&self) -> Option<Runs<'a>> {
+        let mut builder = Runs::builder();
+        builder
+            .category(self.category.clone()?)
+            .status(RunStatus::Verified)
+            .orderby(RunsSorting::Date)
+            .direction(Direction::Asc);
+        if let Some(level) = &self.level {
+            builder.level(level.clone());
+        }
+        if let Some(platform) = &self.platform {
+            builder.platform(platform.clone());
+        }
+        if let Some(region) = &self.region {
+            builder.region(region.clone());
+        }
+        if self.emulators == Some(true) {
+            builder.emulated(true);
+        }
+        Some(builder.build().expect("all required fields are set"))
+    }
+}
+
+impl<'a> LeaderboardBuilder<'a> {
+    /// Add a single custom variable to filter runs by.
+    pub fn variable<Var, Val>(&mut self, variable: Var, value: Val) -> &mut Self
+    where
+        Var: Into<VariableId<'a>>,
+        Val: Into<ValueId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .insert(variable.into(), value.into());
+        self
+    }
+
+    /// Add multiple custom variables to filter runs by.
+    pub fn variables<I, Var, Val>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = (Var, Val)>,
+        Var: Into<VariableId<'a>>,
+        Val: Into<ValueId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .extend(iter.into_iter().map(|(k, v)| (k.into(), v.into())));
+        self
+    }
+}
+
+/// Time of `run` in seconds using `timing`, or the primary time when unset.
+///
+/// Returns `None` if the run has no time for the timing method.
+pub(crate) fn time_for(run: &types::Run<'_>, timing: Option<&TimingMethod>) -> Option<f64> {
+    let times = &run.times;
+    let (time, seconds) = match timing {
+        None => (Some(&times.primary), times.primary_t),
+        Some(TimingMethod::Realtime) => (times.realtime.as_ref(), times.realtime_t),
+        Some(TimingMethod::RealtimeNoloads) => {
+            (times.realtime_noloads.as_ref(), times.realtime_noloads_t)
+        }
+        Some(TimingMethod::Ingame) => (times.ingame.as_ref(), times.ingame_t),
+    };
+    time.filter(|time| !time.is_empty()).map(|_| seconds)
+}
+
+/// Identifies the players of a run regardless of their order.
+pub(crate) fn players_key(run: &types::Run<'_>) -> Vec<String> {
+    let mut key: Vec<String> = run
+        .players
+        .iter()
+        .map(|player| match player {
+            types::Player::User { id, .. } => format!("user:{id}"),
+            types::Player::Guest { name, .. } => format!("guest:{}", name.to_lowercase()),
+        })
+        .collect();
+    key.sort();
+    key
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn run(id: &str, player: &str, time: f64, date: &str, platform: &str) -> types::Run<'static> {
+        let mut run = fixtures::run_json(id, player, time, date);
+        run["system"]["platform"] = json!(platform);
+        serde_json::from_value(run).unwrap()
+    }
+
+    fn places(ranked: &[RankedRun<'_>]) -> Vec<(i64, String)> {
+        ranked
+            .iter()
+            .map(|ranked| (ranked.place, ranked.run.id.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn rank_runs() {
+        let runs = vec![
+            run("a1", "a", 100.0, "2020-01-01", "ps2"),
+            run("a2", "a", 90.0, "2021-01-01", "ps2"),
+            run("b1", "b", 95.0, "2020-06-01", "ps2"),
+            run("c1", "c", 95.0, "2020-07-01", "ps2"),
+            run("d1", "d", 80.0, "2020-01-01", "pc"),
+        ];
+
+        let board = Leaderboard::builder().category("c").build().unwrap();
+        assert_eq!(
+            places(&board.rank(runs.clone())),
+            [(1, "d1"), (2, "a2"), (3, "b1"), (3, "c1")].map(|(p, id)| (p, id.to_owned()))
+        );
+
+        let board = Leaderboard::builder()
+            .platform("ps2")
+            .date("2020-12-31")
+            .top(2)
+            .build()
+            .unwrap();
+        assert_eq!(
+            places(&board.rank(runs)),
+            [(1, "b1"), (1, "c1")].map(|(p, id)| (p, id.to_owned()))
+        );
+    }
+}
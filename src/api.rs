@@ -58,12 +58,12 @@
 
 mod client;
 mod common;
-mod endpoint;
-mod error;
+pub(crate) mod endpoint;
+pub(crate) mod error;
 mod pagination;
-mod query;
-mod query_params;
-mod utils;
+pub(crate) mod query;
+pub(crate) mod query_params;
+pub(crate) mod utils;
 
 pub mod categories;
 pub mod developers;
@@ -74,6 +74,7 @@ pub mod genres;
 pub mod guests;
 pub mod leaderboards;
 pub mod levels;
+pub mod links;
 pub mod notifications;
 pub mod platforms;
 pub mod profile;
@@ -31,6 +31,12 @@ where
     /// The URL failed to parse.
     #[error("url parse error: {0}")]
     Parse(#[from] url::ParseError),
+    /// The URL of the endpoint is not under the client's base URL.
+    #[error("URL is not under the client's base URL: {url}")]
+    ForeignUrl {
+        /// URL of the endpoint
+        url: url::Url,
+    },
     /// The endpoint requires an API key to use, but none was provided.
     #[error("Endpoint requires authentication, but no API key was provided")]
     RequiresAuthentication,
@@ -56,4 +62,15 @@ where
     pub(crate) fn from_http_response(source: ResponseError, url: http::Uri) -> Self {
         Self::Response { source, url }
     }
+
+    /// If the API answered `404 Not Found`.
+    pub(crate) fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::Response {
+                source: ResponseError::HttpStatus { status, .. },
+                ..
+            } if *status == http::StatusCode::NOT_FOUND
+        )
+    }
 }
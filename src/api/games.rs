@@ -169,11 +169,17 @@ pub struct Games<'a> {
 }
 
 /// Retrieves a single game, identified by ID.
-#[derive(Debug, Builder, Clone)]
+#[derive(Debug, Builder, Serialize, Clone)]
 #[builder(setter(into, strip_option))]
+#[serde(rename_all = "kebab-case")]
 pub struct Game<'a> {
     #[doc = r"`ID` of the game."]
+    #[serde(skip)]
     id: GameId<'a>,
+    #[builder(setter(name = "_embed"), private, default)]
+    #[serde(serialize_with = "super::utils::serialize_as_csv")]
+    #[serde(skip_serializing_if = "BTreeSet::is_empty")]
+    embed: BTreeSet<GameEmbeds>,
 }
 
 /// Retrieve all categories for the given game.
@@ -319,6 +325,23 @@ impl Game<'_> {
     }
 }
 
+impl GameBuilder<'_> {
+    /// Add an embedded resource to this result.
+    pub fn embed(&mut self, embed: GameEmbeds) -> &mut Self {
+        self.embed.get_or_insert_with(BTreeSet::new).insert(embed);
+        self
+    }
+
+    /// Add multiple embedded resources to this result.
+    pub fn embeds<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: Iterator<Item = GameEmbeds>,
+    {
+        self.embed.get_or_insert_with(BTreeSet::new).extend(iter);
+        self
+    }
+}
+
 impl GameCategories<'_> {
     /// Create a builder for this endpoint.
     pub fn builder<'a>() -> GameCategoriesBuilder<'a> {
@@ -563,6 +586,10 @@ impl Endpoint for Game<'_> {
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/games/{}", self.id).into()
     }
+
+    fn query_parameters(&self) -> Result<QueryParams<'_>, BodyError> {
+        QueryParams::with(self)
+    }
 }
 
 impl Endpoint for GameCategories<'_> {
@@ -0,0 +1,132 @@
+//! # Links
+//!
+//! Endpoint for following the hypermedia links included in API resources.
+use std::borrow::Cow;
+
+use crate::types::Link;
+
+use super::{endpoint::Endpoint, error::BodyError, query_params::QueryParams, Pageable};
+
+/// Retrieves the resource a [`Link`] points to.
+///
+/// The URI of the link must be under the base URL of the client used to query
+/// this endpoint, otherwise the query fails with [`ApiError::ForeignUrl`].
+/// Links to lists of resources (e.g. `runs`) can be paginated using the
+/// [`PagedEndpointExt`] methods.
+///
+/// [`ApiError::ForeignUrl`]: super::ApiError::ForeignUrl
+/// [`PagedEndpointExt`]: super::PagedEndpointExt
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct LinkEndpoint<'a> {
+    #[doc = r"Absolute URI of the linked resource."]
+    uri: Cow<'a, str>,
+}
+
+impl LinkEndpoint<'_> {
+    /// Create a builder for this endpoint.
+    pub fn builder<'a>() -> LinkEndpointBuilder<'a> {
+        LinkEndpointBuilder::default()
+    }
+
+    fn split_query(&self) -> (&str, Option<&str>) {
+        let uri = match self.uri.split_once('#') {
+            Some((uri, _fragment)) => uri,
+            None => &self.uri,
+        };
+        match uri.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (uri, None),
+        }
+    }
+}
+
+impl Endpoint for LinkEndpoint<'_> {
+    fn endpoint(&self) -> Cow<'static, str> {
+        self.split_query().0.to_owned().into()
+    }
+
+    fn query_parameters(&self) -> Result<QueryParams<'_>, BodyError> {
+        let mut params = QueryParams::default();
+        if let (_, Some(query)) = self.split_query() {
+            params.extend_pairs(form_urlencoded::parse(query.as_bytes()));
+        }
+        Ok(params)
+    }
+}
+
+impl<'a> From<&'a Link> for LinkEndpoint<'a> {
+    fn from(value: &'a Link) -> Self {
+        Self {
+            uri: Cow::Borrowed(&value.uri),
+        }
+    }
+}
+
+impl From<Link> for LinkEndpoint<'static> {
+    fn from(value: Link) -> Self {
+        Self {
+            uri: Cow::Owned(value.uri),
+        }
+    }
+}
+
+impl Pageable for LinkEndpoint<'_> {}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        api::{ApiError, PagedEndpointExt, Query},
+        mock::{self, MockClient},
+    };
+
+    fn link(uri: &str) -> LinkEndpoint<'_> {
+        LinkEndpoint::builder().uri(uri).build().unwrap()
+    }
+
+    #[test]
+    fn follow_link_under_base_url() {
+        let client = MockClient::new(|request| match request.path() {
+            "runs/r1" => Some(json!({ "id": "r1" })),
+            _ => None,
+        });
+        let run: Value = link("https://www.speedrun.com/api/v1/runs/r1?embed=game")
+            .query(&client)
+            .unwrap();
+        assert_eq!(run, json!({ "id": "r1" }));
+        assert_eq!(client.requests()[0].query("embed").as_deref(), Some("game"));
+    }
+
+    #[test]
+    fn refuse_foreign_links() {
+        let client = MockClient::new(|_| Some(Value::Null));
+        for uri in [
+            "https://example.com/api/v1/runs/r1",
+            "http://www.speedrun.com/api/v1/runs/r1",
+        ] {
+            let result: Result<Value, _> = link(uri).query(&client);
+            assert!(
+                matches!(result, Err(ApiError::ForeignUrl { .. })),
+                "{uri} was followed"
+            );
+        }
+        assert!(client.requests().is_empty());
+    }
+
+    #[test]
+    fn page_link_without_duplicate_keys() {
+        let runs: Vec<Value> = (0..25).map(|n| json!({ "id": n })).collect();
+        let client = MockClient::new(move |request| Some(mock::page(request, &runs)));
+        let next = link("https://www.speedrun.com/api/v1/runs?game=g&offset=20&max=20");
+        let runs: Vec<Value> = next.iter(&client).collect::<Result<_, _>>().unwrap();
+        assert_eq!(runs.len(), 25);
+        for request in client.requests() {
+            assert_eq!(request.query_all("game"), ["g"]);
+            assert_eq!(request.query_all("offset").len(), 1);
+            assert!(request.query_all("max").len() <= 1);
+        }
+    }
+}
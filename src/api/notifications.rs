@@ -4,7 +4,10 @@
 
 use serde::Serialize;
 
-use super::{endpoint::Endpoint, error::BodyError, query_params::QueryParams, Direction};
+use super::{
+    endpoint::Endpoint, error::BodyError, pagination::Pageable, query_params::QueryParams,
+    Direction,
+};
 
 /// Sorting options for notifications
 #[derive(Debug, Clone, Serialize, Copy)]
@@ -50,3 +53,5 @@ impl Endpoint for Notifications {
         true
     }
 }
+
+impl Pageable for Notifications {}
@@ -7,6 +7,7 @@ use crate::types::Pagination;
 use super::{
     endpoint::Endpoint,
     query::{AsyncQuery, Query},
+    query_params::QueryParams,
     utils::{build_paged_request, deserialize_response},
     ApiError, AsyncClient, Client, RestClient,
 };
@@ -137,7 +138,14 @@ where
     ) -> Result<url::Url, ApiError<C::Error>> {
         let mut url = client.rest_endpoint(&self.inner.endpoint())?;
 
-        let mut params = self.inner.query_parameters()?;
+        // Links returned by the API may already point to a page; this page's
+        // `offset` and `max` replace theirs.
+        let query = self.inner.query_parameters()?.finish();
+        let mut params = QueryParams::default();
+        params.extend_pairs(
+            form_urlencoded::parse(query.as_bytes())
+                .filter(|(key, _)| !matches!(key.as_ref(), "offset" | "max")),
+        );
         params.extend_from(&self)?;
         params.apply_to(&mut url);
 
@@ -113,8 +113,9 @@ pub enum SplitsIo {
 
 // Does this belong here?
 /// Type of the variable value.
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
+#[serde(tag = "type")]
 pub enum ValueType<'a> {
     /// Pre-defined variable
     PreDefined {
@@ -123,8 +124,8 @@ pub enum ValueType<'a> {
     },
     /// User defined variable
     UserDefined {
-        /// Value ID
-        value: ValueId<'a>,
+        /// Free text value
+        value: Cow<'a, str>,
     },
 }
 
@@ -223,50 +224,50 @@ pub struct Run<'a> {
 #[serde(rename_all = "kebab-case")]
 pub struct CreateRun<'a> {
     #[doc = r"Category ID for the run."]
-    category: CategoryId<'a>,
+    pub(crate) category: CategoryId<'a>,
     #[doc = r"Level ID for individual level runs."]
     #[builder(default)]
-    level: Option<LevelId<'a>>,
+    pub(crate) level: Option<LevelId<'a>>,
     #[doc = r"Optional date the run was performed (defaults to the current date)."]
     #[builder(default)]
-    date: Option<Cow<'a, str>>,
+    pub(crate) date: Option<Cow<'a, str>>,
     #[doc = r"Optional region for the run. Some games require a region to be submitted."]
     #[builder(default)]
-    region: Option<RegionId<'a>>,
+    pub(crate) region: Option<RegionId<'a>>,
     #[doc = r"Optional platform for the run. Some games require a platform to be submitted."]
     #[builder(default)]
-    platform: Option<PlatformId<'a>>,
+    pub(crate) platform: Option<PlatformId<'a>>,
     #[doc = r"If the run has been verified by a moderator. Can only be set if the submitting user is a moderator of the game."]
     #[builder(default)]
-    verified: Option<bool>,
+    pub(crate) verified: Option<bool>,
     #[builder(setter(name = "_times"), private, default)]
-    times: Times,
+    pub(crate) times: Times,
     #[builder(setter(name = "_players"), private, default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    players: Vec<Player<'a>>,
+    pub(crate) players: Vec<Player<'a>>,
     #[doc = r"When `true` the run was performed on an emulator (default: false)."]
-    emulated: Option<bool>,
+    pub(crate) emulated: Option<bool>,
     #[doc = r"A valid video URL. Optional, but some games require a video to be included."]
     #[builder(default)]
-    video: Option<url::Url>,
+    pub(crate) video: Option<url::Url>,
     #[doc = r"Optional comment on the run. Can include additional video URLs."]
     #[builder(default)]
-    comment: Option<String>,
+    pub(crate) comment: Option<String>,
     #[doc = r"Splits.io ID or URL for the splits for the run."]
     #[builder(default)]
-    splitsio: Option<SplitsIo>,
+    pub(crate) splitsio: Option<SplitsIo>,
     #[doc = r"Variable values for the new run. Some games have mandatory variables."]
     #[builder(default)]
     #[serde(skip_serializing_if = "HashMap::is_empty")]
-    variables: HashMap<VariableId<'a>, ValueType<'a>>,
+    pub(crate) variables: HashMap<VariableId<'a>, ValueType<'a>>,
 }
 
 #[derive(Default, Debug, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
-struct Times {
-    realtime: Option<f64>,
-    realtime_noloads: Option<f64>,
-    ingame: Option<f64>,
+pub(crate) struct Times {
+    pub(crate) realtime: Option<f64>,
+    pub(crate) realtime_noloads: Option<f64>,
+    pub(crate) ingame: Option<f64>,
 }
 
 /// Update the verification status for the run.
@@ -392,6 +393,49 @@ impl<'a> CreateRunBuilder<'a> {
         self
     }
 
+    /// Set the value of a variable to one of its pre-defined values.
+    pub fn variable<V, T>(&mut self, variable: V, value: T) -> &mut Self
+    where
+        V: Into<VariableId<'a>>,
+        T: Into<ValueId<'a>>,
+    {
+        self.variable_value(
+            variable,
+            ValueType::PreDefined {
+                value: value.into(),
+            },
+        )
+    }
+
+    /// Set the value of a user-defined variable to free text.
+    pub fn user_defined_variable<V, T>(&mut self, variable: V, value: T) -> &mut Self
+    where
+        V: Into<VariableId<'a>>,
+        T: Into<Cow<'a, str>>,
+    {
+        self.variable_value(
+            variable,
+            ValueType::UserDefined {
+                value: value.into(),
+            },
+        )
+    }
+
+    /// Set the value of a variable.
+    ///
+    /// See [`Variable::value_for`] for picking the value by its label.
+    ///
+    /// [`Variable::value_for`]: crate::types::Variable::value_for
+    pub fn variable_value<V>(&mut self, variable: V, value: ValueType<'a>) -> &mut Self
+    where
+        V: Into<VariableId<'a>>,
+    {
+        self.variables
+            .get_or_insert_with(HashMap::new)
+            .insert(variable.into(), value);
+        self
+    }
+
     fn validate(&self) -> Result<(), String> {
         if let Some(times) = &self.times {
             if times.realtime.is_none()
@@ -555,3 +599,29 @@ impl From<&RunEmbeds> for &'static str {
 }
 
 impl Pageable for Runs<'_> {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn create_run_variables() {
+        let run = CreateRun::buider()
+            .category("cat")
+            .realtime(60.0)
+            .emulated(false)
+            .variable("ver", "jp")
+            .user_defined_variable("seed", "1234")
+            .build()
+            .unwrap();
+
+        let body = serde_json::to_value(&run).unwrap();
+        assert_eq!(
+            body["variables"],
+            serde_json::json!({
+                "ver": { "type": "pre-defined", "value": "jp" },
+                "seed": { "type": "user-defined", "value": "1234" },
+            })
+        );
+    }
+}
@@ -27,7 +27,8 @@ where
     E: Endpoint,
     C: RestClient,
 {
-    let url = client.rest_endpoint(&endpoint.endpoint())?;
+    let mut url = client.rest_endpoint(&endpoint.endpoint())?;
+    endpoint.query_parameters()?.apply_to(&mut url);
     build_request_internal(url, endpoint, client)
 }
 
@@ -39,13 +40,14 @@ where
     E: Endpoint + Pageable,
     C: RestClient,
 {
+    // The page URL already carries the endpoint's query parameters.
     let url = page.page_url(client)?;
     let endpoint = page.inner;
     build_request_internal(url, endpoint, client)
 }
 
 pub(crate) fn build_request_internal<E, C>(
-    mut url: url::Url,
+    url: url::Url,
     endpoint: &E,
     client: &C,
 ) -> Result<(RequestBuilder, Vec<u8>), ApiError<C::Error>>
@@ -57,7 +59,12 @@ where
         return Err(ApiError::RequiresAuthentication);
     }
 
-    endpoint.query_parameters()?.apply_to(&mut url);
+    // Endpoints built from links returned by the API use absolute URLs; make sure
+    // those never leave the API (along with the API key).
+    let base = client.rest_endpoint("")?;
+    if !url.as_str().starts_with(base.as_str()) {
+        return Err(ApiError::ForeignUrl { url });
+    }
 
     let req = RequestBuilder::new()
         .method(endpoint.method())
@@ -87,11 +94,20 @@ pub enum ResponseError {
     },
 }
 
-pub(crate) fn deserialize_response<T>(
-    rsp: http::Response<Bytes>,
-) -> Result<Root<T>, ResponseError>
+pub(crate) fn deserialize_response<T>(rsp: http::Response<Bytes>) -> Result<Root<T>, ResponseError>
 where
     T: DeserializeOwned,
+{
+    deserialize_json(rsp, std::any::type_name::<T>())
+}
+
+/// Deserialize a JSON response body into `R`, reporting `typ` on failure.
+pub(crate) fn deserialize_json<R>(
+    rsp: http::Response<Bytes>,
+    typ: &'static str,
+) -> Result<R, ResponseError>
+where
+    R: DeserializeOwned,
 {
     let status = rsp.status();
     let value = serde_json::from_slice(rsp.body())?;
@@ -99,9 +115,9 @@ where
         return Err(ResponseError::HttpStatus { value, status });
     }
 
-    serde_json::from_value::<Root<T>>(value.clone()).map_err(|err| ResponseError::DataType {
+    serde_json::from_value::<R>(value.clone()).map_err(|err| ResponseError::DataType {
         source: err,
         value,
-        typ: std::any::type_name::<T>(),
+        typ,
     })
 }
@@ -0,0 +1,723 @@
+//! # Archive
+//!
+//! Downloads everything about one game into a single, self-contained JSON
+//! document: the game with all embeds, its categories, levels and variables,
+//! every leaderboard, every run (of any status) and the users and guests
+//! referenced by the runs.
+//!
+//! Resources are stored as returned by the API, so that an [`Archive`] keeps
+//! fields this crate does not model. The document is built step by step and
+//! can be saved after every step, so an interrupted download resumes where it
+//! stopped.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{archive::Archive, SpeedrunApiBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//!
+//! // Resumes from `smw.json` if a previous download was interrupted.
+//! let archive = Archive::download_to_async(&client, "smw", "smw.json").await?;
+//! println!("{} runs archived", archive.runs.len());
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        games::{Game, GameCategories, GameEmbeds, GameId, GameLevels, GameVariables},
+        guests::Guest,
+        runs::{Runs, RunsSorting},
+        users::User,
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, Query, SinglePage,
+    },
+    boards::{self, Board},
+    types::{self, Pagination},
+};
+
+/// Version of the archive format written by this crate.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Number of runs requested per page.
+const PAGE_SIZE: usize = 200;
+
+/// Number of requests between saves of an archive file.
+const CHECKPOINT_INTERVAL: usize = 25;
+
+/// Errors that occur when downloading an archive.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ArchiveError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The archive file could not be read or written.
+    #[error("archive file error: {0}")]
+    Io(#[from] io::Error),
+    /// The archive file was written by an incompatible version.
+    #[error("unsupported archive version {0}")]
+    UnsupportedVersion(u32),
+    /// The archive file belongs to a different game.
+    #[error("archive file is for game `{0}`")]
+    OtherGame(String),
+    /// The requested game does not exist.
+    #[error("game `{0}` not found")]
+    GameNotFound(String),
+}
+
+/// A complete (or partially downloaded) copy of a game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Archive {
+    /// Version of the archive format, see [`ARCHIVE_VERSION`].
+    pub version: u32,
+    /// The game `ID` or abbreviation the archive was requested for.
+    pub requested: String,
+    /// Seconds since the Unix epoch when the download started.
+    pub started: u64,
+    /// If every resource was downloaded.
+    pub complete: bool,
+    /// The game, with all embeds.
+    pub game: Option<Value>,
+    /// Categories of the game.
+    pub categories: Option<Vec<Value>>,
+    /// Levels of the game.
+    pub levels: Option<Vec<Value>>,
+    /// Variables of the game.
+    pub variables: Option<Vec<Value>>,
+    /// Every leaderboard of the game, see [`boards`].
+    pub leaderboards: Vec<ArchivedLeaderboard>,
+    /// Every run of the game, in order of submission.
+    pub runs: Vec<Value>,
+    /// If all runs were downloaded.
+    pub runs_complete: bool,
+    /// `ID`s of users and names of guests referenced by the downloaded runs.
+    pub referenced: Referenced,
+    /// Users referenced by the runs, by `ID`. `null` for users that no longer
+    /// exist.
+    pub users: BTreeMap<String, Value>,
+    /// Guests referenced by the runs, by name. `null` for guests that no
+    /// longer exist.
+    pub guests: BTreeMap<String, Value>,
+}
+
+/// A leaderboard in an [`Archive`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedLeaderboard {
+    /// Human readable label of the board.
+    pub label: String,
+    /// `ID` of the category.
+    pub category: String,
+    /// `ID` of the level for individual-level boards.
+    pub level: Option<String>,
+    /// Selected subcategory values.
+    pub values: BTreeMap<String, String>,
+    /// The leaderboard.
+    pub leaderboard: Value,
+}
+
+/// Players and examiners referenced by the runs of an [`Archive`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Referenced {
+    /// `ID`s of users.
+    pub users: BTreeSet<String>,
+    /// Names of guests.
+    pub guests: BTreeSet<String>,
+}
+
+impl Referenced {
+    /// Add the players and examiners of `runs`.
+    fn add(&mut self, runs: &[Value]) {
+        for run in parse_all::<types::Run<'static>>(runs) {
+            for player in &run.players {
+                match player {
+                    types::Player::User { id, .. } => self.users.insert(id.to_string()),
+                    types::Player::Guest { name, .. } => self.guests.insert(name.clone()),
+                };
+            }
+            match &run.status {
+                types::Status::Verified {
+                    examiner: Some(examiner),
+                    ..
+                }
+                | types::Status::Rejected { examiner, .. } => {
+                    self.users.insert(examiner.to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A single request needed to complete an archive.
+enum Step {
+    Game,
+    Categories,
+    Levels,
+    Variables,
+    Leaderboard(Box<Board<'static>>),
+    Runs(usize),
+    User(String),
+    Guest(String),
+}
+
+/// Result of a [`Step`].
+enum Fetched {
+    One(Value),
+    Page(Vec<Value>),
+    Missing,
+}
+
+impl Archive {
+    /// Create an empty archive of `game` (an `ID` or abbreviation).
+    pub fn new<G: Into<String>>(game: G) -> Self {
+        Self {
+            version: ARCHIVE_VERSION,
+            requested: game.into(),
+            started: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+            complete: false,
+            game: None,
+            categories: None,
+            levels: None,
+            variables: None,
+            leaderboards: Vec::new(),
+            runs: Vec::new(),
+            runs_complete: false,
+            referenced: Referenced::default(),
+            users: BTreeMap::new(),
+            guests: BTreeMap::new(),
+        }
+    }
+
+    /// Load an archive from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+
+    /// Save the archive as a JSON file.
+    ///
+    /// The file is replaced atomically, so an interrupted save keeps the
+    /// previous contents.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)
+    }
+
+    /// Download the archive of `game` using `client`.
+    pub fn download<G, C>(client: &C, game: G) -> Result<Self, ArchiveError<C::Error>>
+    where
+        G: Into<String>,
+        C: Client,
+    {
+        let mut archive = Self::new(game);
+        archive.resume(client, |_| Ok(()))?;
+        Ok(archive)
+    }
+
+    /// Download the archive of `game` using `client`, saving it to `path`
+    /// periodically and when the download stops.
+    ///
+    /// If `path` contains an incomplete archive of the same game, the download
+    /// continues from there.
+    pub fn download_to<G, P, C>(
+        client: &C,
+        game: G,
+        path: P,
+    ) -> Result<Self, ArchiveError<C::Error>>
+    where
+        G: Into<String>,
+        P: AsRef<Path>,
+        C: Client,
+    {
+        let path = path.as_ref();
+        let mut archive = Self::open(game.into(), path)?;
+        let mut steps = 0;
+        let result = archive.resume(client, |archive| checkpoint(archive, path, &mut steps));
+        archive.save(path)?;
+        result.map(|_| archive)
+    }
+
+    /// Download the archive of `game` asynchronously using `client`.
+    pub async fn download_async<G, C>(client: &C, game: G) -> Result<Self, ArchiveError<C::Error>>
+    where
+        G: Into<String>,
+        C: AsyncClient + Sync,
+    {
+        let mut archive = Self::new(game);
+        archive.resume_async(client, |_| Ok(())).await?;
+        Ok(archive)
+    }
+
+    /// Download the archive of `game` asynchronously using `client`, saving it
+    /// to `path` periodically and when the download stops.
+    ///
+    /// If `path` contains an incomplete archive of the same game, the download
+    /// continues from there.
+    pub async fn download_to_async<G, P, C>(
+        client: &C,
+        game: G,
+        path: P,
+    ) -> Result<Self, ArchiveError<C::Error>>
+    where
+        G: Into<String>,
+        P: AsRef<Path>,
+        C: AsyncClient + Sync,
+    {
+        let path = path.as_ref();
+        let mut archive = Self::open(game.into(), path)?;
+        let mut steps = 0;
+        let result = archive
+            .resume_async(client, |archive| checkpoint(archive, path, &mut steps))
+            .await;
+        archive.save(path)?;
+        result.map(|_| archive)
+    }
+
+    /// Download the missing parts of the archive, calling `checkpoint` after
+    /// every request.
+    pub fn resume<C, F>(
+        &mut self,
+        client: &C,
+        mut checkpoint: F,
+    ) -> Result<(), ArchiveError<C::Error>>
+    where
+        C: Client,
+        F: FnMut(&Self) -> io::Result<()>,
+    {
+        let mut boards = None;
+        while let Some(step) = self.next_step(&mut boards) {
+            let fetched = match self.fetch(&step, client) {
+                Ok(fetched) => fetched,
+                Err(err) if err.is_not_found() => self.missing(&step)?,
+                Err(err) => return Err(err.into()),
+            };
+            self.apply(step, fetched);
+            checkpoint(self)?;
+        }
+        self.complete = true;
+        Ok(())
+    }
+
+    /// Download the missing parts of the archive asynchronously, calling
+    /// `checkpoint` after every request.
+    pub async fn resume_async<C, F>(
+        &mut self,
+        client: &C,
+        mut checkpoint: F,
+    ) -> Result<(), ArchiveError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+        F: FnMut(&Self) -> io::Result<()>,
+    {
+        let mut boards = None;
+        while let Some(step) = self.next_step(&mut boards) {
+            let fetched = match self.fetch_async(&step, client).await {
+                Ok(fetched) => fetched,
+                Err(err) if err.is_not_found() => self.missing(&step)?,
+                Err(err) => return Err(err.into()),
+            };
+            self.apply(step, fetched);
+            checkpoint(self)?;
+        }
+        self.complete = true;
+        Ok(())
+    }
+
+    /// The archived game.
+    pub fn parsed_game(&self) -> Option<types::Game<'static>> {
+        serde_json::from_value(self.game.clone()?).ok()
+    }
+
+    /// The archived runs that could be parsed.
+    pub fn parsed_runs(&self) -> impl Iterator<Item = types::Run<'static>> + '_ {
+        self.runs
+            .iter()
+            .filter_map(|run| serde_json::from_value(run.clone()).ok())
+    }
+
+    fn open<C>(game: String, path: &Path) -> Result<Self, ArchiveError<C>>
+    where
+        C: std::error::Error + Send + Sync + 'static,
+    {
+        let archive = match Self::load(path) {
+            Ok(archive) => archive,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::new(game)),
+            Err(err) => return Err(err.into()),
+        };
+        if archive.version != ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(archive.version));
+        }
+        if archive.requested != game {
+            return Err(ArchiveError::OtherGame(archive.requested));
+        }
+        Ok(archive)
+    }
+
+    /// `ID` of the game, once it was downloaded.
+    fn game_id(&self) -> Option<GameId<'static>> {
+        let id = self.game.as_ref()?.get("id")?.as_str()?;
+        Some(GameId::new(id.to_owned()))
+    }
+
+    /// The next request to make. `boards` caches the boards of the game once
+    /// its categories, levels and variables are known.
+    fn next_step(&self, boards: &mut Option<Vec<Board<'static>>>) -> Option<Step> {
+        if self.complete {
+            return None;
+        }
+        let Some(game) = self.game_id() else {
+            return Some(Step::Game);
+        };
+        let Some(categories) = &self.categories else {
+            return Some(Step::Categories);
+        };
+        let Some(levels) = &self.levels else {
+            return Some(Step::Levels);
+        };
+        let Some(variables) = &self.variables else {
+            return Some(Step::Variables);
+        };
+        let boards = boards.get_or_insert_with(|| all_boards(&game, categories, levels, variables));
+        if let Some(board) = boards.get(self.leaderboards.len()) {
+            return Some(Step::Leaderboard(Box::new(board.clone())));
+        }
+        if !self.runs_complete {
+            return Some(Step::Runs(self.runs.len()));
+        }
+
+        let referenced = &self.referenced;
+        if let Some(user) = referenced
+            .users
+            .iter()
+            .find(|id| !self.users.contains_key(*id))
+        {
+            return Some(Step::User(user.clone()));
+        }
+        if let Some(guest) = referenced
+            .guests
+            .iter()
+            .find(|name| !self.guests.contains_key(*name))
+        {
+            return Some(Step::Guest(guest.clone()));
+        }
+        None
+    }
+
+    fn fetch<C>(&self, step: &Step, client: &C) -> Result<Fetched, ApiError<C::Error>>
+    where
+        C: Client,
+    {
+        Ok(match step {
+            Step::Game => Fetched::One(game_endpoint(&self.requested).query(client)?),
+            Step::Categories => Fetched::Page(self.categories_endpoint().query(client)?),
+            Step::Levels => Fetched::Page(self.levels_endpoint().query(client)?),
+            Step::Variables => Fetched::Page(self.variables_endpoint().query(client)?),
+            Step::Leaderboard(board) => Fetched::One(board.endpoint.query(client)?),
+            Step::Runs(offset) => {
+                let runs = self.runs_endpoint();
+                let (page, _): (Vec<Value>, Pagination) = SinglePage::builder(&runs)
+                    .offset(*offset)
+                    .page_size(PAGE_SIZE)
+                    .build()
+                    .query(client)?;
+                Fetched::Page(page)
+            }
+            Step::User(id) => Fetched::One(user_endpoint(id).query(client)?),
+            Step::Guest(name) => Fetched::One(guest_endpoint(name).query(client)?),
+        })
+    }
+
+    async fn fetch_async<C>(&self, step: &Step, client: &C) -> Result<Fetched, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        Ok(match step {
+            Step::Game => Fetched::One(game_endpoint(&self.requested).query_async(client).await?),
+            Step::Categories => {
+                Fetched::Page(self.categories_endpoint().query_async(client).await?)
+            }
+            Step::Levels => Fetched::Page(self.levels_endpoint().query_async(client).await?),
+            Step::Variables => Fetched::Page(self.variables_endpoint().query_async(client).await?),
+            Step::Leaderboard(board) => Fetched::One(board.endpoint.query_async(client).await?),
+            Step::Runs(offset) => {
+                let runs = self.runs_endpoint();
+                let (page, _): (Vec<Value>, Pagination) = SinglePage::builder(&runs)
+                    .offset(*offset)
+                    .page_size(PAGE_SIZE)
+                    .build()
+                    .query_async(client)
+                    .await?;
+                Fetched::Page(page)
+            }
+            Step::User(id) => Fetched::One(user_endpoint(id).query_async(client).await?),
+            Step::Guest(name) => Fetched::One(guest_endpoint(name).query_async(client).await?),
+        })
+    }
+
+    fn apply(&mut self, step: Step, fetched: Fetched) {
+        let (value, page) = match fetched {
+            Fetched::One(value) => (value, Vec::new()),
+            Fetched::Page(page) => (Value::Null, page),
+            Fetched::Missing => (Value::Null, Vec::new()),
+        };
+        match step {
+            Step::Game => self.game = Some(value),
+            Step::Categories => self.categories = Some(page),
+            Step::Levels => self.levels = Some(page),
+            Step::Variables => self.variables = Some(page),
+            Step::Leaderboard(board) => self.leaderboards.push(ArchivedLeaderboard {
+                label: board.label,
+                category: board.category.to_string(),
+                level: board.level.as_ref().map(ToString::to_string),
+                values: board
+                    .values
+                    .iter()
+                    .map(|(var, val)| (var.to_string(), val.to_string()))
+                    .collect(),
+                leaderboard: value,
+            }),
+            Step::Runs(_) => {
+                self.runs_complete = page.len() < PAGE_SIZE;
+                self.referenced.add(&page);
+                self.runs.extend(page);
+            }
+            Step::User(id) => {
+                self.users.insert(id, value);
+            }
+            Step::Guest(name) => {
+                self.guests.insert(name, value);
+            }
+        }
+    }
+
+    /// Result of a `step` whose resource does not exist.
+    fn missing<E>(&self, step: &Step) -> Result<Fetched, ArchiveError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match step {
+            Step::Game => Err(ArchiveError::GameNotFound(self.requested.clone())),
+            _ => Ok(Fetched::Missing),
+        }
+    }
+
+    fn categories_endpoint(&self) -> GameCategories<'static> {
+        GameCategories::builder()
+            .id(self.game_id().expect("game was downloaded"))
+            .build()
+            .expect("all required fields are set")
+    }
+
+    fn levels_endpoint(&self) -> GameLevels<'static> {
+        GameLevels::builder()
+            .id(self.game_id().expect("game was downloaded"))
+            .build()
+            .expect("all required fields are set")
+    }
+
+    fn variables_endpoint(&self) -> GameVariables<'static> {
+        GameVariables::builder()
+            .id(self.game_id().expect("game was downloaded"))
+            .build()
+            .expect("all required fields are set")
+    }
+
+    fn runs_endpoint(&self) -> Runs<'static> {
+        Runs::builder()
+            .game(self.game_id().expect("game was downloaded"))
+            .orderby(RunsSorting::Submitted)
+            .direction(Direction::Asc)
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+/// Save `archive` to `path` every [`CHECKPOINT_INTERVAL`] requests.
+fn checkpoint(archive: &Archive, path: &Path, steps: &mut usize) -> io::Result<()> {
+    *steps += 1;
+    if *steps == CHECKPOINT_INTERVAL {
+        *steps = 0;
+        archive.save(path)?;
+    }
+    Ok(())
+}
+
+fn game_endpoint(game: &str) -> Game<'_> {
+    Game::builder()
+        .id(game)
+        .embeds(
+            [
+                GameEmbeds::Levels,
+                GameEmbeds::Categories,
+                GameEmbeds::Moderators,
+                GameEmbeds::Gametypes,
+                GameEmbeds::Platforms,
+                GameEmbeds::Regions,
+                GameEmbeds::Genres,
+                GameEmbeds::Engines,
+                GameEmbeds::Developers,
+                GameEmbeds::Publishers,
+                GameEmbeds::Variables,
+            ]
+            .into_iter(),
+        )
+        .build()
+        .expect("all required fields are set")
+}
+
+fn user_endpoint(id: &str) -> User<'_> {
+    User::builder()
+        .id(id)
+        .build()
+        .expect("all required fields are set")
+}
+
+fn guest_endpoint(name: &str) -> Guest<'_> {
+    Guest::builder()
+        .name(name)
+        .build()
+        .expect("all required fields are set")
+}
+
+/// Every board of every category, in a stable order.
+fn all_boards(
+    game: &GameId<'static>,
+    categories: &[Value],
+    levels: &[Value],
+    variables: &[Value],
+) -> Vec<Board<'static>> {
+    let levels: Vec<types::Level<'static>> = parse_all(levels);
+    let variables: Vec<types::Variable<'static>> = parse_all(variables);
+    parse_all::<types::Category<'static>>(categories)
+        .iter()
+        .flat_map(|category| {
+            let applicable: Vec<_> = variables
+                .iter()
+                .filter(|variable| {
+                    variable
+                        .category
+                        .as_ref()
+                        .is_none_or(|id| *id == category.id)
+                })
+                .cloned()
+                .collect();
+            boards::boards(game, category, &applicable, &levels)
+        })
+        .collect()
+}
+
+fn parse_all<T: serde::de::DeserializeOwned>(values: &[Value]) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|value| serde_json::from_value(value.clone()).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::mock::{self, MockClient};
+
+    #[test]
+    fn archive_steps() {
+        let mut archive = Archive::new("smw");
+        let mut boards = None;
+        assert!(matches!(archive.next_step(&mut boards), Some(Step::Game)));
+        archive.apply(Step::Game, Fetched::One(json!({ "id": "g" })));
+        archive.apply(Step::Categories, Fetched::Page(Vec::new()));
+        archive.apply(Step::Levels, Fetched::Page(Vec::new()));
+        archive.apply(Step::Variables, Fetched::Page(Vec::new()));
+        assert!(matches!(
+            archive.next_step(&mut boards),
+            Some(Step::Runs(0))
+        ));
+
+        let run = json!({
+            "id": "r",
+            "weblink": "",
+            "game": "g",
+            "category": "c",
+            "status": { "status": "rejected", "examiner": "mod", "reason": "" },
+            "players": [
+                { "rel": "user", "id": "u", "uri": "" },
+                { "rel": "guest", "name": "someone", "uri": "" }
+            ],
+            "times": { "primary": "", "primary_t": 0.0, "realtime": null, "realtime_t": 0.0,
+                       "realtime_noloads": null, "realtime_noloads_t": 0.0,
+                       "ingame": null, "ingame_t": 0.0 },
+            "system": { "platform": null, "emulated": false, "region": null }
+        });
+        archive.apply(Step::Runs(0), Fetched::Page(vec![run]));
+        assert!(archive.runs_complete);
+
+        let mut fetched = Vec::new();
+        while let Some(step) = archive.next_step(&mut boards) {
+            match &step {
+                Step::User(id) | Step::Guest(id) => fetched.push(id.clone()),
+                _ => unreachable!(),
+            }
+            archive.apply(step, Fetched::Missing);
+        }
+        assert_eq!(fetched, ["mod", "u", "someone"]);
+        assert_eq!(archive.users.get("u"), Some(&Value::Null));
+    }
+
+    #[test]
+    fn archive_paged_runs() {
+        let runs: Vec<Value> = (0..PAGE_SIZE + 50)
+            .map(|n| json!({ "id": format!("r{}", n), "players": [] }))
+            .collect();
+        let client = MockClient::new(move |request| match request.path() {
+            "games/smw" => Some(json!({ "id": "g" })),
+            "games/g/categories" | "games/g/levels" | "games/g/variables" => Some(json!([])),
+            "runs" => Some(mock::page(request, &runs)),
+            _ => None,
+        });
+
+        let archive = Archive::download(&client, "smw").unwrap();
+        assert!(archive.complete);
+        assert_eq!(archive.runs.len(), PAGE_SIZE + 50);
+        assert_eq!(client.requests().len(), 6);
+
+        let pages: Vec<_> = client
+            .requests()
+            .into_iter()
+            .filter(|request| request.path() == "runs")
+            .collect();
+        assert_eq!(pages.len(), 2);
+        for (page, offset) in pages.iter().zip(["0", "200"]) {
+            assert_eq!(page.query("game").as_deref(), Some("g"));
+            assert_eq!(page.query("offset").as_deref(), Some(offset));
+            assert_eq!(page.query("max").as_deref(), Some("200"));
+        }
+    }
+
+    #[test]
+    fn archive_missing_game() {
+        let client = MockClient::new(|_| None);
+        assert!(matches!(
+            Archive::download(&client, "smw"),
+            Err(ArchiveError::GameNotFound(game)) if game == "smw"
+        ));
+    }
+}
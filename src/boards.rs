@@ -0,0 +1,371 @@
+//! # Boards
+//!
+//! Enumerates every leaderboard of a category, including one board per
+//! combination of subcategory values (variables flagged `is-subcategory`).
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{api::AsyncQuery, boards, types, SpeedrunApiBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//!
+//! for board in boards::category_boards_async(&client, "v1pxjz68", "n2y5jzd3").await? {
+//!     let leaderboard: types::Leaderboard = board.endpoint.query_async(&client).await?;
+//!     println!("{}: {} runs", board.label, leaderboard.runs.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+
+use crate::{
+    api::{
+        categories::{Category, CategoryId, CategoryVariables},
+        endpoint::Endpoint,
+        error::BodyError,
+        games::{GameId, GameLevels},
+        leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard},
+        levels::LevelId,
+        query_params::QueryParams,
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, Query,
+    },
+    types::{self, CategoryType, Scope},
+};
+
+/// Separator used between the parts of a board label.
+const LABEL_SEPARATOR: &str = " – ";
+
+/// A single leaderboard of a category.
+#[derive(Debug, Clone)]
+pub struct Board<'a> {
+    /// Human readable label, e.g. "Any% – No Major Glitches".
+    pub label: String,
+    /// Category of the board.
+    pub category: CategoryId<'a>,
+    /// Level of the board for individual-level categories.
+    pub level: Option<LevelId<'a>>,
+    /// Selected subcategory values.
+    pub values: Vec<(VariableId<'a>, ValueId<'a>)>,
+    /// Endpoint retrieving the board.
+    pub endpoint: BoardEndpoint<'a>,
+}
+
+/// Endpoint retrieving a [`Board`].
+#[derive(Debug, Clone)]
+pub enum BoardEndpoint<'a> {
+    /// A full-game leaderboard.
+    FullGame(FullGameLeaderboard<'a>),
+    /// An individual-level leaderboard.
+    IndividualLevel(IndividualLevelLeaderboard<'a>),
+}
+
+/// Enumerates all boards of `category` from already retrieved resources.
+///
+/// `variables` are the variables applicable to the category (see
+/// [`CategoryVariables`]). `levels` are only used for per-level categories,
+/// one set of boards is created for every level.
+pub fn boards<'a>(
+    game: &GameId<'a>,
+    category: &types::Category<'a>,
+    variables: &[types::Variable<'a>],
+    levels: &[types::Level<'a>],
+) -> Vec<Board<'a>> {
+    match category.type_field {
+        CategoryType::PerGame => {
+            let subcategories = subcategories(variables, |scope| {
+                matches!(scope, Scope::Global | Scope::FullGame)
+            });
+            combinations(&subcategories)
+                .into_iter()
+                .map(|values| {
+                    let endpoint = FullGameLeaderboard::builder()
+                        .game(game.clone())
+                        .category(category.id.clone())
+                        .variables(owned_values(&values))
+                        .build()
+                        .expect("all required fields are set");
+                    Board {
+                        label: label(None, category, &values),
+                        category: category.id.clone(),
+                        level: None,
+                        values: owned_values(&values),
+                        endpoint: BoardEndpoint::FullGame(endpoint),
+                    }
+                })
+                .collect()
+        }
+        CategoryType::PerLevel => levels
+            .iter()
+            .flat_map(|level| {
+                let subcategories = subcategories(variables, |scope| match scope {
+                    Scope::Global | Scope::AllLevels => true,
+                    Scope::SingleLevel { level: id } => level.id.to_string() == *id,
+                    Scope::FullGame => false,
+                });
+                combinations(&subcategories)
+                    .into_iter()
+                    .map(|values| {
+                        let endpoint = IndividualLevelLeaderboard::builder()
+                            .game(game.clone())
+                            .level(level.id.clone())
+                            .category(category.id.clone())
+                            .variables(owned_values(&values))
+                            .build()
+                            .expect("all required fields are set");
+                        Board {
+                            label: label(Some(level), category, &values),
+                            category: category.id.clone(),
+                            level: Some(level.id.clone()),
+                            values: owned_values(&values),
+                            endpoint: BoardEndpoint::IndividualLevel(endpoint),
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect(),
+    }
+}
+
+/// Retrieves the category, its variables and (for per-level categories) the
+/// levels of `game`, and enumerates all boards of the category.
+pub fn category_boards<'a, G, Cat, C>(
+    client: &C,
+    game: G,
+    category: Cat,
+) -> Result<Vec<Board<'static>>, ApiError<C::Error>>
+where
+    G: Into<GameId<'a>>,
+    Cat: Into<CategoryId<'a>>,
+    C: Client,
+{
+    let game = owned_game_id(game.into());
+    let category = category.into();
+
+    let (category_endpoint, variables_endpoint) = category_endpoints(category);
+    let category: types::Category<'static> = category_endpoint.query(client)?;
+    let variables: Vec<types::Variable<'static>> = variables_endpoint.query(client)?;
+    let levels: Vec<types::Level<'static>> = match category.type_field {
+        CategoryType::PerGame => Vec::new(),
+        CategoryType::PerLevel => levels_endpoint(&game).query(client)?,
+    };
+
+    Ok(boards(&game, &category, &variables, &levels))
+}
+
+/// Retrieves the category, its variables and (for per-level categories) the
+/// levels of `game` asynchronously, and enumerates all boards of the category.
+pub async fn category_boards_async<'a, G, Cat, C>(
+    client: &C,
+    game: G,
+    category: Cat,
+) -> Result<Vec<Board<'static>>, ApiError<C::Error>>
+where
+    G: Into<GameId<'a>>,
+    Cat: Into<CategoryId<'a>>,
+    C: AsyncClient + Sync,
+{
+    let game = owned_game_id(game.into());
+    let category = category.into();
+
+    let (category_endpoint, variables_endpoint) = category_endpoints(category);
+    let category: types::Category<'static> = category_endpoint.query_async(client).await?;
+    let variables: Vec<types::Variable<'static>> = variables_endpoint.query_async(client).await?;
+    let levels: Vec<types::Level<'static>> = match category.type_field {
+        CategoryType::PerGame => Vec::new(),
+        CategoryType::PerLevel => levels_endpoint(&game).query_async(client).await?,
+    };
+
+    Ok(boards(&game, &category, &variables, &levels))
+}
+
+fn category_endpoints(category: CategoryId<'_>) -> (Category<'_>, CategoryVariables<'_>) {
+    let category_endpoint = Category::builder()
+        .id(category.clone())
+        .build()
+        .expect("all required fields are set");
+    let variables_endpoint = CategoryVariables::builder()
+        .id(category)
+        .build()
+        .expect("all required fields are set");
+    (category_endpoint, variables_endpoint)
+}
+
+fn levels_endpoint<'a>(game: &GameId<'a>) -> GameLevels<'a> {
+    GameLevels::builder()
+        .id(game.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn owned_game_id(game: GameId<'_>) -> GameId<'static> {
+    GameId::new(game.to_string())
+}
+
+/// Subcategory variables whose scope matches, with their values sorted by
+/// label.
+fn subcategories<'v, 'a>(
+    variables: &'v [types::Variable<'a>],
+    in_scope: impl Fn(&Scope) -> bool,
+) -> Vec<(
+    &'v types::Variable<'a>,
+    Vec<(&'v ValueId<'a>, &'v types::Value)>,
+)> {
+    variables
+        .iter()
+        .filter(|variable| variable.is_subcategory && in_scope(&variable.scope))
+        .map(|variable| {
+            let mut values: Vec<_> = variable.values.values.iter().collect();
+            values.sort_by(|(_, a), (_, b)| a.label.cmp(&b.label));
+            (variable, values)
+        })
+        .collect()
+}
+
+type Selection<'v, 'a> = Vec<(&'v VariableId<'a>, (&'v ValueId<'a>, &'v types::Value))>;
+
+/// Cartesian product of the values of all subcategory variables.
+fn combinations<'v, 'a>(
+    subcategories: &[(
+        &'v types::Variable<'a>,
+        Vec<(&'v ValueId<'a>, &'v types::Value)>,
+    )],
+) -> Vec<Selection<'v, 'a>> {
+    subcategories
+        .iter()
+        .fold(vec![Vec::new()], |acc, (variable, values)| {
+            acc.into_iter()
+                .flat_map(|selection| {
+                    values.iter().map(move |value| {
+                        let mut selection = selection.clone();
+                        selection.push((&variable.id, *value));
+                        selection
+                    })
+                })
+                .collect()
+        })
+}
+
+fn label(
+    level: Option<&types::Level<'_>>,
+    category: &types::Category<'_>,
+    values: &Selection<'_, '_>,
+) -> String {
+    level
+        .map(|level| level.name.as_str())
+        .into_iter()
+        .chain(Some(category.name.as_str()))
+        .chain(values.iter().map(|(_, (_, value))| value.label.as_str()))
+        .collect::<Vec<_>>()
+        .join(LABEL_SEPARATOR)
+}
+
+fn owned_values<'a>(values: &Selection<'_, 'a>) -> Vec<(VariableId<'a>, ValueId<'a>)> {
+    values
+        .iter()
+        .map(|(var, (val, _))| ((*var).clone(), (*val).clone()))
+        .collect()
+}
+
+impl<'a> From<&types::Leaderboard<'a>> for BoardEndpoint<'a> {
+    fn from(value: &types::Leaderboard<'a>) -> Self {
+        match IndividualLevelLeaderboard::try_from(value) {
+            Ok(endpoint) => BoardEndpoint::IndividualLevel(endpoint),
+            Err(_) => BoardEndpoint::FullGame(
+                FullGameLeaderboard::try_from(value).expect("leaderboard has no level"),
+            ),
+        }
+    }
+}
+
+impl Endpoint for BoardEndpoint<'_> {
+    fn endpoint(&self) -> Cow<'static, str> {
+        match self {
+            BoardEndpoint::FullGame(endpoint) => endpoint.endpoint(),
+            BoardEndpoint::IndividualLevel(endpoint) => endpoint.endpoint(),
+        }
+    }
+
+    fn query_parameters(&self) -> Result<QueryParams<'_>, BodyError> {
+        match self {
+            BoardEndpoint::FullGame(endpoint) => endpoint.query_parameters(),
+            BoardEndpoint::IndividualLevel(endpoint) => endpoint.query_parameters(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures::{category, variable_json};
+
+    #[test]
+    fn full_game_combinations() {
+        let variables: Vec<types::Variable<'static>> = serde_json::from_value(json!([
+            variable_json(
+                "glitch",
+                json!({ "type": "global" }),
+                &[("a", "NMG"), ("b", "Glitched")]
+            ),
+            variable_json(
+                "ver",
+                json!({ "type": "full-game" }),
+                &[("c", "JP"), ("d", "US")]
+            ),
+            variable_json("lvl", json!({ "type": "all-levels" }), &[("e", "Unused")]),
+        ]))
+        .unwrap();
+
+        let boards = boards(&GameId::new("game"), &category("per-game"), &variables, &[]);
+        let labels: Vec<_> = boards.iter().map(|board| board.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Any% – Glitched – JP",
+                "Any% – Glitched – US",
+                "Any% – NMG – JP",
+                "Any% – NMG – US",
+            ]
+        );
+        assert!(boards
+            .iter()
+            .all(|board| matches!(board.endpoint, BoardEndpoint::FullGame(_))));
+    }
+
+    #[test]
+    fn per_level_scopes() {
+        let variables: Vec<types::Variable<'static>> = serde_json::from_value(json!([
+            variable_json(
+                "star",
+                json!({ "type": "single-level", "level": "l1" }),
+                &[("a", "Red"), ("b", "Blue")]
+            ),
+            variable_json("ver", json!({ "type": "full-game" }), &[("c", "JP")]),
+        ]))
+        .unwrap();
+        let levels: Vec<types::Level<'static>> = serde_json::from_value(json!([
+            { "id": "l1", "name": "Bianco Hills", "weblink": "", "links": [] },
+            { "id": "l2", "name": "Ricco Harbor", "weblink": "", "links": [] },
+        ]))
+        .unwrap();
+
+        let boards = boards(
+            &GameId::new("game"),
+            &category("per-level"),
+            &variables,
+            &levels,
+        );
+        let labels: Vec<_> = boards.iter().map(|board| board.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            [
+                "Bianco Hills – Any% – Blue",
+                "Bianco Hills – Any% – Red",
+                "Ricco Harbor – Any%",
+            ]
+        );
+    }
+}
@@ -82,18 +82,7 @@ impl api::Client for SpeedrunApiClient {
         let call = || -> Result<_, RestError> {
             self.api_key
                 .set_auth_header(request.headers_mut().unwrap())?;
-            let http_request = request.body(body)?;
-            let request = http_request.try_into()?;
-            let rsp = self.client.execute(request)?;
-
-            let mut http_rsp = http::Response::builder()
-                .status(rsp.status())
-                .version(rsp.version());
-            let headers = http_rsp.headers_mut().unwrap();
-            for (key, val) in rsp.headers() {
-                headers.insert(key, val.clone());
-            }
-            http_rsp.body(rsp.bytes()?).map_err(From::from)
+            execute(&self.client, request.body(body)?)
         };
         call().map_err(api::ApiError::client)
     }
@@ -170,23 +159,46 @@ impl api::AsyncClient for SpeedrunApiClientAsync {
         let call = || async {
             self.api_key
                 .set_auth_header(request.headers_mut().unwrap())?;
-            let http_request = request.body(body)?;
-            let request = http_request.try_into()?;
-            let rsp = self.client.execute(request).await?;
-
-            let mut http_rsp = http::Response::builder()
-                .status(rsp.status())
-                .version(rsp.version());
-            let headers = http_rsp.headers_mut().unwrap();
-            for (key, val) in rsp.headers() {
-                headers.insert(key, val.clone());
-            }
-            http_rsp.body(rsp.bytes().await?).map_err(From::from)
+            execute_async(&self.client, request.body(body)?).await
         };
         call().map_err(api::ApiError::client).await
     }
 }
 
+/// Send `request` with a blocking `reqwest` client.
+pub(crate) fn execute(
+    client: &HttpClient,
+    request: http::Request<Vec<u8>>,
+) -> Result<http::Response<bytes::Bytes>, RestError> {
+    let rsp = client.execute(request.try_into()?)?;
+
+    let mut http_rsp = http::Response::builder()
+        .status(rsp.status())
+        .version(rsp.version());
+    let headers = http_rsp.headers_mut().unwrap();
+    for (key, val) in rsp.headers() {
+        headers.insert(key, val.clone());
+    }
+    http_rsp.body(rsp.bytes()?).map_err(From::from)
+}
+
+/// Send `request` with an asynchronous `reqwest` client.
+pub(crate) async fn execute_async(
+    client: &AsyncHttpClient,
+    request: http::Request<Vec<u8>>,
+) -> Result<http::Response<bytes::Bytes>, RestError> {
+    let rsp = client.execute(request.try_into()?).await?;
+
+    let mut http_rsp = http::Response::builder()
+        .status(rsp.status())
+        .version(rsp.version());
+    let headers = http_rsp.headers_mut().unwrap();
+    for (key, val) in rsp.headers() {
+        headers.insert(key, val.clone());
+    }
+    http_rsp.body(rsp.bytes().await?).map_err(From::from)
+}
+
 /// Speedrun.com API client builder
 #[derive(Debug, Default)]
 pub struct SpeedrunApiBuilder {
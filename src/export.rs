@@ -0,0 +1,576 @@
+//! # Export
+//!
+//! Writes runs and leaderboards as CSV or JSON Lines, one run per row, for
+//! spreadsheets and other tools.
+//!
+//! Every row has the same columns (see [`COLUMNS`]). `ID`s are resolved to
+//! names when the response embedded the resources (see [`Embedded`]), or when
+//! they are passed to [`Exporter::with_names`]; otherwise the `ID` is written.
+//!
+//! ```rust ,no_run
+//! use std::fs::File;
+//!
+//! use speedrun_api::{
+//!     api::{
+//!         leaderboards::{FullGameLeaderboard, LeaderboardEmbeds},
+//!         runs::{RunEmbeds, Runs},
+//!         AsyncQuery, PagedEndpointExt,
+//!     },
+//!     export::{Exporter, Format},
+//!     types::{Embedded, Leaderboard, Run},
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//!
+//! let endpoint = Runs::builder()
+//!     .game("o1y9wo6q")
+//!     .embeds([RunEmbeds::Game, RunEmbeds::Category, RunEmbeds::Players].into_iter())
+//!     .build()?;
+//! let mut runs = Exporter::new(File::create("runs.csv")?, Format::Csv);
+//! runs.write_stream(endpoint.stream::<Embedded<Run<'static>>, _>(&client))
+//!     .await?;
+//! runs.finish()?;
+//!
+//! let endpoint = FullGameLeaderboard::builder()
+//!     .game("o1y9wo6q")
+//!     .category("wkpoo02r")
+//!     .embeds([LeaderboardEmbeds::Players, LeaderboardEmbeds::Variables].into_iter())
+//!     .build()?;
+//! let leaderboard: Embedded<Leaderboard<'static>> = endpoint.query_async(&client).await?;
+//! let mut board = Exporter::new(File::create("board.jsonl")?, Format::JsonLines);
+//! board.write_leaderboard(&leaderboard)?;
+//! board.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::BTreeMap, io, io::Write};
+
+use futures::{Stream, TryStreamExt};
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    api::{users::UserId, variables::ValueId, ApiError},
+    types::{self, Embedded, Embeds, Status},
+};
+
+/// Names of the columns, in order.
+pub const COLUMNS: [&str; 18] = [
+    "run_id",
+    "game",
+    "category",
+    "level",
+    "place",
+    "players",
+    "primary_t",
+    "realtime_t",
+    "realtime_noloads_t",
+    "ingame_t",
+    "platform",
+    "region",
+    "emulated",
+    "date",
+    "submitted",
+    "status",
+    "examiner",
+    "variables",
+];
+
+/// Errors that occur when exporting runs from the API.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ExportError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The output could not be written.
+    #[error("write error: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Output format of an [`Exporter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Comma separated values with a header row. Players are separated by
+    /// `"; "`, variables are written as `name=label` pairs separated by
+    /// `"; "`.
+    Csv,
+    /// One JSON object per line. Players are an array, variables an object.
+    JsonLines,
+}
+
+/// A single exported run. Fields are in the order of [`COLUMNS`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Row {
+    /// `ID` of the run.
+    pub run_id: String,
+    /// Name of the game.
+    pub game: String,
+    /// Name of the category.
+    pub category: String,
+    /// Name of the level, for individual-level runs.
+    pub level: Option<String>,
+    /// Place on the leaderboard, for runs exported from a leaderboard.
+    pub place: Option<i64>,
+    /// Names of the players.
+    pub players: Vec<String>,
+    /// Time in seconds, using the default timing method of the game.
+    pub primary_t: f64,
+    /// Real time in seconds.
+    pub realtime_t: Option<f64>,
+    /// Real time without loads in seconds.
+    pub realtime_noloads_t: Option<f64>,
+    /// In-game time in seconds.
+    pub ingame_t: Option<f64>,
+    /// Name of the platform.
+    pub platform: Option<String>,
+    /// Name of the region.
+    pub region: Option<String>,
+    /// If the run was done on an emulator.
+    pub emulated: bool,
+    /// Date the run was done.
+    pub date: Option<String>,
+    /// Date and time the run was submitted.
+    pub submitted: Option<String>,
+    /// `new`, `verified` or `rejected`.
+    pub status: String,
+    /// Name of the moderator who verified or rejected the run.
+    pub examiner: Option<String>,
+    /// Labels of the selected values, by variable name. Variables sharing a
+    /// name are suffixed with their `ID` in parentheses.
+    pub variables: BTreeMap<String, String>,
+}
+
+/// A run that can be exported: a [`types::Run`], optionally [`Embedded`].
+pub trait ExportRun {
+    /// The run.
+    fn run(&self) -> &types::Run<'_>;
+
+    /// Resources embedded in the response of the run.
+    fn embeds(&self) -> Option<&Embeds<'_>> {
+        None
+    }
+}
+
+/// A leaderboard that can be exported: a [`types::Leaderboard`], optionally
+/// [`Embedded`].
+pub trait ExportLeaderboard {
+    /// The leaderboard.
+    fn leaderboard(&self) -> &types::Leaderboard<'_>;
+
+    /// Resources embedded in the response of the leaderboard.
+    fn embeds(&self) -> Option<&Embeds<'_>> {
+        None
+    }
+}
+
+/// Writes runs to `W` in a [`Format`].
+#[derive(Debug)]
+pub struct Exporter<W> {
+    writer: W,
+    format: Format,
+    names: Embeds<'static>,
+    header: bool,
+}
+
+impl<W: Write> Exporter<W> {
+    /// Create an exporter writing to `writer`.
+    pub fn new(writer: W, format: Format) -> Self {
+        Self {
+            writer,
+            format,
+            names: Embeds::default(),
+            header: false,
+        }
+    }
+
+    /// Resolve `ID`s that were not embedded in the exported responses using
+    /// `names`, e.g. the categories, levels and variables of a game.
+    pub fn with_names(mut self, names: Embeds<'static>) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Write a single run.
+    pub fn write_run<R>(&mut self, run: &R) -> io::Result<()>
+    where
+        R: ExportRun + ?Sized,
+    {
+        let row = self.row(run.run(), None, run.embeds());
+        self.write_row(&row)
+    }
+
+    /// Write all `runs`, returning the number of runs written.
+    pub fn write_runs<I>(&mut self, runs: I) -> io::Result<usize>
+    where
+        I: IntoIterator,
+        I::Item: ExportRun,
+    {
+        let mut written = 0;
+        for run in runs {
+            self.write_run(&run)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Write the runs of `stream` (e.g. from
+    /// [`crate::api::PagedEndpointExt::stream`]), returning the number of runs
+    /// written.
+    pub async fn write_stream<S, R, E>(&mut self, stream: S) -> Result<usize, ExportError<E>>
+    where
+        S: Stream<Item = Result<R, ApiError<E>>>,
+        R: ExportRun,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        futures::pin_mut!(stream);
+        let mut written = 0;
+        while let Some(run) = stream.try_next().await? {
+            self.write_run(&run)?;
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    /// Write the runs of `leaderboard` in order, returning the number of runs
+    /// written.
+    pub fn write_leaderboard<L>(&mut self, leaderboard: &L) -> io::Result<usize>
+    where
+        L: ExportLeaderboard + ?Sized,
+    {
+        let embeds = leaderboard.embeds();
+        let leaderboard = leaderboard.leaderboard();
+        for ranked in &leaderboard.runs {
+            let row = self.row(&ranked.run, Some(ranked.place), embeds);
+            self.write_row(&row)?;
+        }
+        Ok(leaderboard.runs.len())
+    }
+
+    /// Write the header if no run was written, flush, and return the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_header()?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn row(&self, run: &types::Run<'_>, place: Option<i64>, embeds: Option<&Embeds<'_>>) -> Row {
+        let sources: Vec<&Embeds<'_>> = embeds.into_iter().chain([&self.names]).collect();
+        let user = |id: &UserId<'_>| {
+            lookup(&sources, |embeds| {
+                Some(embeds.user(id)?.names.international.clone())
+            })
+            .unwrap_or_else(|| id.to_string())
+        };
+        let (status, examiner) = match &run.status {
+            Status::New => ("new", None),
+            Status::Verified { examiner, .. } => ("verified", examiner.as_ref().map(user)),
+            Status::Rejected { examiner, .. } => ("rejected", Some(user(examiner))),
+        };
+        let times = &run.times;
+
+        let values: Vec<(String, String, String)> = run
+            .values
+            .iter()
+            .map(|(id, value)| {
+                let variable = sources.iter().find_map(|embeds| embeds.variable(id));
+                let name =
+                    variable.map_or_else(|| id.to_string(), |variable| variable.name.clone());
+                let label = variable
+                    .and_then(|variable| variable.values.values.get(&ValueId::new(value.clone())))
+                    .map_or_else(|| value.clone(), |value| value.label.clone());
+                (id.to_string(), name, label)
+            })
+            .collect();
+        let mut variables = BTreeMap::new();
+        for (id, name, label) in &values {
+            let shared = values.iter().filter(|(_, other, _)| other == name).count() > 1;
+            let key = if shared {
+                format!("{name} ({id})")
+            } else {
+                name.clone()
+            };
+            variables.insert(key, label.clone());
+        }
+
+        Row {
+            run_id: run.id.to_string(),
+            game: lookup(&sources, |embeds| {
+                Some(embeds.game(&run.game)?.names.international.clone())
+            })
+            .unwrap_or_else(|| run.game.to_string()),
+            category: lookup(&sources, |embeds| {
+                Some(embeds.category(&run.category)?.name.clone())
+            })
+            .unwrap_or_else(|| run.category.to_string()),
+            level: run.level.as_ref().map(|level| {
+                lookup(&sources, |embeds| Some(embeds.level(level)?.name.clone()))
+                    .unwrap_or_else(|| level.to_string())
+            }),
+            place,
+            players: run
+                .players
+                .iter()
+                .map(|player| match player {
+                    types::Player::User { id, .. } => user(id),
+                    types::Player::Guest { name, .. } => name.clone(),
+                })
+                .collect(),
+            primary_t: times.primary_t,
+            realtime_t: times.realtime.as_ref().map(|_| times.realtime_t),
+            realtime_noloads_t: times
+                .realtime_noloads
+                .as_ref()
+                .map(|_| times.realtime_noloads_t),
+            ingame_t: times.ingame.as_ref().map(|_| times.ingame_t),
+            platform: run.system.platform.as_ref().map(|platform| {
+                lookup(&sources, |embeds| {
+                    Some(embeds.platform(platform)?.name.clone())
+                })
+                .unwrap_or_else(|| platform.to_string())
+            }),
+            region: run.system.region.as_ref().map(|region| {
+                lookup(&sources, |embeds| Some(embeds.region(region)?.name.clone()))
+                    .unwrap_or_else(|| region.to_string())
+            }),
+            emulated: run.system.emulated,
+            date: run.date.clone(),
+            submitted: run.submitted.clone(),
+            status: status.to_owned(),
+            examiner,
+            variables,
+        }
+    }
+
+    fn write_row(&mut self, row: &Row) -> io::Result<()> {
+        self.write_header()?;
+        match self.format {
+            Format::Csv => {
+                let optional =
+                    |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+                let variables: Vec<String> = row
+                    .variables
+                    .iter()
+                    .map(|(name, label)| format!("{name}={label}"))
+                    .collect();
+                let fields = [
+                    csv_field(&row.run_id),
+                    csv_field(&row.game),
+                    csv_field(&row.category),
+                    csv_field(row.level.as_deref().unwrap_or_default()),
+                    row.place.map(|place| place.to_string()).unwrap_or_default(),
+                    csv_field(&row.players.join("; ")),
+                    row.primary_t.to_string(),
+                    optional(row.realtime_t),
+                    optional(row.realtime_noloads_t),
+                    optional(row.ingame_t),
+                    csv_field(row.platform.as_deref().unwrap_or_default()),
+                    csv_field(row.region.as_deref().unwrap_or_default()),
+                    row.emulated.to_string(),
+                    csv_field(row.date.as_deref().unwrap_or_default()),
+                    csv_field(row.submitted.as_deref().unwrap_or_default()),
+                    row.status.clone(),
+                    csv_field(row.examiner.as_deref().unwrap_or_default()),
+                    csv_field(&variables.join("; ")),
+                ];
+                writeln!(self.writer, "{}", fields.join(","))
+            }
+            Format::JsonLines => {
+                serde_json::to_writer(&mut self.writer, row)?;
+                writeln!(self.writer)
+            }
+        }
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        if !std::mem::replace(&mut self.header, true) && self.format == Format::Csv {
+            writeln!(self.writer, "{}", COLUMNS.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+impl ExportRun for types::Run<'_> {
+    fn run(&self) -> &types::Run<'_> {
+        self
+    }
+}
+
+impl ExportRun for Embedded<types::Run<'_>> {
+    fn run(&self) -> &types::Run<'_> {
+        &self.data
+    }
+
+    fn embeds(&self) -> Option<&Embeds<'_>> {
+        Some(&self.embeds)
+    }
+}
+
+impl<T: ExportRun + ?Sized> ExportRun for &T {
+    fn run(&self) -> &types::Run<'_> {
+        (**self).run()
+    }
+
+    fn embeds(&self) -> Option<&Embeds<'_>> {
+        (**self).embeds()
+    }
+}
+
+impl ExportLeaderboard for types::Leaderboard<'_> {
+    fn leaderboard(&self) -> &types::Leaderboard<'_> {
+        self
+    }
+}
+
+impl ExportLeaderboard for Embedded<types::Leaderboard<'_>> {
+    fn leaderboard(&self) -> &types::Leaderboard<'_> {
+        &self.data
+    }
+
+    fn embeds(&self) -> Option<&Embeds<'_>> {
+        Some(&self.embeds)
+    }
+}
+
+/// Name found by `find` in the first of `sources` containing it.
+fn lookup<F>(sources: &[&Embeds<'_>], find: F) -> Option<String>
+where
+    F: Fn(&Embeds<'_>) -> Option<String>,
+{
+    sources.iter().find_map(|embeds| find(embeds))
+}
+
+/// Quote `field` if it contains a delimiter, quote or line break.
+pub(crate) fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn export_embedded_runs() {
+        let run: Embedded<types::Run<'static>> = serde_json::from_value(json!({
+            "id": "r1",
+            "weblink": "",
+            "game": "g",
+            "level": { "data": [] },
+            "category": { "data": {
+                "id": "c", "name": "Any%, glitched", "weblink": "", "type": "per-game",
+                "rules": null, "players": { "type": "exactly", "value": 1 },
+                "miscellaneous": false, "links": []
+            } },
+            "status": { "status": "new" },
+            "players": { "data": [
+                { "rel": "guest", "name": "someone", "links": [] }
+            ] },
+            "date": "2021-01-01",
+            "submitted": null,
+            "times": {
+                "primary": "PT1M", "primary_t": 60.0,
+                "realtime": "PT1M", "realtime_t": 60.0,
+                "realtime_noloads": null, "realtime_noloads_t": 0.0,
+                "ingame": null, "ingame_t": 0.0
+            },
+            "system": { "platform": "p", "emulated": false, "region": null },
+            "values": { "v": "x" }
+        }))
+        .unwrap();
+
+        let mut csv = Exporter::new(Vec::new(), Format::Csv);
+        csv.write_runs([&run]).unwrap();
+        let csv = String::from_utf8(csv.finish().unwrap()).unwrap();
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("r1,g,\"Any%, glitched\",,,someone,60,60,,,p,,false,2021-01-01,,new,,v=x")
+        );
+
+        let mut jsonl = Exporter::new(Vec::new(), Format::JsonLines);
+        jsonl.write_run(&run).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&jsonl.finish().unwrap()).unwrap();
+        assert_eq!(line["players"], json!(["someone"]));
+        assert_eq!(line["variables"], json!({ "v": "x" }));
+    }
+
+    #[test]
+    fn export_leaderboard() {
+        let mut first = fixtures::run_json("r1", "u1", 100.0, "2021-01-01");
+        first["status"]["examiner"] = json!("u2");
+        let second = fixtures::run_json("r2", "u3", 110.0, "2021-01-02");
+        let leaderboard: Embedded<types::Leaderboard<'static>> = serde_json::from_value(json!({
+            "weblink": "",
+            "game": "g",
+            "category": "c",
+            "video-only": false,
+            "runs": [{ "place": 1, "run": first }, { "place": 2, "run": second }],
+            "links": [],
+            "players": { "data": [
+                fixtures::user_json("u1", "Alice"),
+                fixtures::user_json("u2", "Bob"),
+            ] }
+        }))
+        .unwrap();
+
+        let mut csv = Exporter::new(Vec::new(), Format::Csv);
+        assert_eq!(csv.write_leaderboard(&leaderboard).unwrap(), 2);
+        let csv = String::from_utf8(csv.finish().unwrap()).unwrap();
+        assert_eq!(
+            csv.lines().skip(1).collect::<Vec<_>>(),
+            [
+                "r1,g,c,,1,Alice,100,100,,,,,false,2021-01-01,,verified,Bob,",
+                "r2,g,c,,2,u3,110,110,,,,,false,2021-01-02,,verified,,",
+            ]
+        );
+    }
+
+    #[test]
+    fn export_with_names() {
+        let mut run = fixtures::run_json("r1", "u1", 60.0, "2021-01-01");
+        run["status"] = json!({ "status": "rejected", "examiner": "u2", "reason": "no video" });
+        run["values"] = json!({ "v1": "a", "v2": "b", "v3": "c", "v4": "d" });
+        let run: types::Run<'static> = serde_json::from_value(run).unwrap();
+
+        let mut variables: Vec<types::Variable<'static>> = serde_json::from_value(json!([
+            fixtures::variable_json("v1", json!({ "type": "global" }), &[("a", "1.0")]),
+            fixtures::variable_json("v2", json!({ "type": "global" }), &[("b", "Japan")]),
+            fixtures::variable_json("v3", json!({ "type": "global" }), &[]),
+        ]))
+        .unwrap();
+        variables[0].name = "Version".into();
+        variables[1].name = "Version".into();
+        let mut category = fixtures::category_json("per-game");
+        category["id"] = json!("c");
+        let names = Embeds {
+            category: Some(serde_json::from_value(category).unwrap()),
+            players: vec![serde_json::from_value(fixtures::user_json("u2", "Bob")).unwrap()],
+            variables,
+            ..Embeds::default()
+        };
+
+        let mut exporter = Exporter::new(Vec::new(), Format::JsonLines).with_names(names);
+        exporter.write_run(&run).unwrap();
+        let line: serde_json::Value = serde_json::from_slice(&exporter.finish().unwrap()).unwrap();
+        assert_eq!(line["game"], "g");
+        assert_eq!(line["category"], "Any%");
+        assert_eq!(line["players"], json!(["u1"]));
+        assert_eq!(line["examiner"], "Bob");
+        assert_eq!(
+            line["variables"],
+            json!({ "Version (v1)": "1.0", "Version (v2)": "Japan", "v3": "c", "v4": "d" })
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Resources shared by the tests, built from the JSON returned by the API.
+use serde_json::{json, Value};
+
+use crate::types;
+
+/// The `game` game, played on `pc` and timed in real time.
+pub(crate) fn game_json() -> Value {
+    let asset = json!({ "uri": null, "width": null, "height": null });
+    json!({
+        "id": "game",
+        "names": { "international": "Game", "japanese": null, "twitch": null },
+        "abbreviation": "game",
+        "weblink": "",
+        "release-date": "2000-01-01",
+        "ruleset": {
+            "show-milliseconds": false,
+            "require-verification": true,
+            "require-video": true,
+            "run-times": ["realtime"],
+            "default-time": "realtime",
+            "emulators-allowed": false,
+        },
+        "gametypes": [],
+        "platforms": ["pc"],
+        "regions": [],
+        "genres": [],
+        "engines": [],
+        "developers": [],
+        "publishers": [],
+        "moderators": {},
+        "created": null,
+        "assets": {
+            "logo": asset, "cover-tiny": asset, "cover-small": asset,
+            "cover-medium": asset, "cover-large": asset, "icon": asset,
+            "trophy-1st": asset, "trophy-2nd": asset, "trophy-3rd": asset,
+        },
+        "links": [],
+    })
+}
+
+/// See [`game_json`].
+pub(crate) fn game() -> types::Game<'static> {
+    serde_json::from_value(game_json()).unwrap()
+}
+
+/// The `cat` category, either `per-game` or `per-level`.
+pub(crate) fn category_json(kind: &str) -> Value {
+    json!({
+        "id": "cat",
+        "name": "Any%",
+        "weblink": "",
+        "type": kind,
+        "rules": null,
+        "players": { "type": "exactly", "value": 1 },
+        "miscellaneous": false,
+        "links": [],
+    })
+}
+
+/// See [`category_json`].
+pub(crate) fn category(kind: &str) -> types::Category<'static> {
+    serde_json::from_value(category_json(kind)).unwrap()
+}
+
+/// A mandatory subcategory with `values` as `(ID, label)` pairs.
+pub(crate) fn variable_json(id: &str, scope: Value, values: &[(&str, &str)]) -> Value {
+    let values: serde_json::Map<_, _> = values
+        .iter()
+        .map(|(id, label)| (id.to_string(), json!({ "label": label, "rules": null })))
+        .collect();
+    json!({
+        "id": id,
+        "name": id,
+        "category": null,
+        "scope": scope,
+        "mandatory": true,
+        "user-defined": false,
+        "obsoletes": true,
+        "values": { "values": values, "default": null },
+        "is-subcategory": true,
+        "links": [],
+    })
+}
+
+/// The user `id`, named `name`.
+pub(crate) fn user_json(id: &str, name: &str) -> Value {
+    json!({
+        "rel": "user",
+        "id": id,
+        "names": { "international": name, "japanese": null, "twitch": null },
+        "pronouns": null,
+        "weblink": "",
+        "name-style": { "style": "solid", "color": { "light": "#000000", "dark": "#ffffff" } },
+        "role": "user",
+        "signup": null,
+        "location": null,
+        "twitch": null,
+        "hitbox": null,
+        "youtube": null,
+        "twitter": null,
+        "speedrunslive": null,
+        "links": [],
+    })
+}
+
+/// A verified run of `player` in the `c` category of `g`.
+pub(crate) fn run_json(id: &str, player: &str, time: f64, date: &str) -> Value {
+    json!({
+        "id": id,
+        "weblink": "",
+        "game": "g",
+        "category": "c",
+        "status": { "status": "verified", "examiner": null, "verify-date": null },
+        "players": [{ "rel": "user", "id": player, "uri": "" }],
+        "date": date,
+        "times": {
+            "primary": "x", "primary_t": time,
+            "realtime": "x", "realtime_t": time,
+            "realtime_noloads": null, "realtime_noloads_t": 0.0,
+            "ingame": null, "ingame_t": 0.0
+        },
+        "system": { "platform": null, "emulated": false, "region": null }
+    })
+}
+
+/// See [`run_json`].
+pub(crate) fn run(id: &str, player: &str, time: f64, date: &str) -> types::Run<'static> {
+    serde_json::from_value(run_json(id, player, time, date)).unwrap()
+}
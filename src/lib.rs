@@ -17,10 +17,29 @@ extern crate derive_builder;
 
 mod auth;
 mod client;
+#[cfg(test)]
+mod fixtures;
+#[cfg(test)]
+mod mock;
+mod text;
 
+pub mod analysis;
 pub mod api;
+pub mod archive;
+pub mod boards;
 pub mod error;
+pub mod export;
+pub mod livesplit;
+pub mod moderation;
+pub mod render;
+pub mod resolver;
+pub mod splitsio;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod submission;
 pub mod types;
+pub mod watch;
+pub mod weblink;
 
 pub use auth::AuthError;
 pub use client::{SpeedrunApiBuilder, SpeedrunApiClient, SpeedrunApiClientAsync};
@@ -0,0 +1,583 @@
+//! # LiveSplit
+//!
+//! Import runs from LiveSplit split files (`.lss`).
+//!
+//! Parsing works entirely offline. The game and category names stored in the
+//! file can then be resolved into IDs with a [`Resolver`], and a chosen
+//! attempt (or the personal best) turned into a pre-filled
+//! [`CreateRunBuilder`].
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     livesplit::{Selection, Splits},
+//!     resolver::Resolver,
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().api_key("KEY").build_async()?;
+//! let mut resolver = Resolver::new(&client);
+//!
+//! let splits = Splits::from_path("Super Mario Sunshine - Any%.lss")?;
+//! let (_game, category) = splits.resolve_async(&mut resolver).await?;
+//! let run = splits
+//!     .run_builder(category, Selection::PersonalBest)?
+//!     .video(url::Url::parse("https://youtu.be/...")?)
+//!     .build()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::BTreeMap, path::Path, str::FromStr};
+
+use roxmltree::{Document, Node};
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        games::GameId,
+        runs::{CreateRun, CreateRunBuilder},
+        AsyncClient, Client,
+    },
+    resolver::{ResolveError, Resolver},
+};
+
+/// Name of the comparison holding the personal best split times.
+const PERSONAL_BEST: &str = "Personal Best";
+
+/// Errors that occur when reading a splits file.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum LiveSplitError {
+    /// The file could not be read.
+    #[error("failed to read splits file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file is not valid XML.
+    #[error("xml parse error: {0}")]
+    Xml(#[from] roxmltree::Error),
+    /// The XML document is not a LiveSplit splits file.
+    #[error("not a LiveSplit splits file (root element `{0}`)")]
+    NotSplits(String),
+    /// A required element is missing.
+    #[error("missing element `{0}`")]
+    MissingElement(&'static str),
+    /// A time could not be parsed.
+    #[error("malformed time `{0}`")]
+    Time(String),
+    /// A numeric attribute could not be parsed.
+    #[error("malformed attempt id `{0}`")]
+    AttemptId(String),
+    /// The selected attempt does not exist.
+    #[error("no attempt with id {0}")]
+    UnknownAttempt(i64),
+    /// The selected attempt was not completed, or there is no personal best.
+    #[error("the selected run has no time")]
+    NoTime,
+}
+
+/// A time measured with both timing methods, in seconds.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Time {
+    /// Real-world time.
+    pub real_time: Option<f64>,
+    /// Time measured by the game (or with loads removed).
+    pub game_time: Option<f64>,
+}
+
+/// A single attempt from the attempt history.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attempt {
+    /// `ID` of the attempt, as used by the segment history.
+    pub id: i64,
+    /// When the attempt was started, as stored by LiveSplit
+    /// (`MM/dd/yyyy HH:mm:ss`, UTC).
+    pub started: Option<String>,
+    /// When the attempt ended.
+    pub ended: Option<String>,
+    /// Final time of the attempt, empty for resets.
+    pub time: Time,
+}
+
+/// A segment (split) of the run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    /// Name of the segment.
+    pub name: String,
+    /// Split time of the personal best at the end of this segment.
+    pub personal_best: Time,
+    /// Best time ever achieved for this segment.
+    pub best_segment: Time,
+    /// Segment times by attempt `ID`.
+    pub history: BTreeMap<i64, Time>,
+}
+
+/// Metadata about the run, used by LiveSplit for speedrun.com submissions.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Metadata {
+    /// Name of the platform.
+    pub platform: Option<String>,
+    /// If the run is done on an emulator.
+    pub uses_emulator: Option<bool>,
+    /// Name of the region.
+    pub region: Option<String>,
+    /// Variable names and values.
+    pub variables: Vec<(String, String)>,
+}
+
+/// Which run to import from the splits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Selection {
+    /// The personal best.
+    PersonalBest,
+    /// A specific attempt by `ID`.
+    Attempt(i64),
+}
+
+/// A parsed LiveSplit splits file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Splits {
+    /// Name of the game.
+    pub game_name: String,
+    /// Name of the category.
+    pub category_name: String,
+    /// Run metadata.
+    pub metadata: Metadata,
+    /// Attempt history, in the order stored in the file.
+    pub attempts: Vec<Attempt>,
+    /// Segments of the run.
+    pub segments: Vec<Segment>,
+}
+
+impl Splits {
+    /// Parse the contents of a splits file.
+    pub fn parse(xml: &str) -> Result<Self, LiveSplitError> {
+        let document = Document::parse(xml)?;
+        let root = document.root_element();
+        if !root.has_tag_name("Run") {
+            return Err(LiveSplitError::NotSplits(root.tag_name().name().to_owned()));
+        }
+
+        let game_name = child_text(root, "GameName")
+            .ok_or(LiveSplitError::MissingElement("GameName"))?
+            .to_owned();
+        let category_name = child_text(root, "CategoryName")
+            .ok_or(LiveSplitError::MissingElement("CategoryName"))?
+            .to_owned();
+        let metadata = child(root, "Metadata")
+            .map(parse_metadata)
+            .unwrap_or_default();
+        let attempts = child(root, "AttemptHistory")
+            .into_iter()
+            .flat_map(|history| children(history, "Attempt"))
+            .map(parse_attempt)
+            .collect::<Result<_, _>>()?;
+        let segments = child(root, "Segments")
+            .into_iter()
+            .flat_map(|segments| children(segments, "Segment"))
+            .map(parse_segment)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            game_name,
+            category_name,
+            metadata,
+            attempts,
+            segments,
+        })
+    }
+
+    /// Read and parse a splits file.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, LiveSplitError> {
+        let xml = std::fs::read_to_string(path)?;
+        Self::parse(&xml)
+    }
+
+    /// The attempt with `id`.
+    pub fn attempt(&self, id: i64) -> Option<&Attempt> {
+        self.attempts.iter().find(|attempt| attempt.id == id)
+    }
+
+    /// Attempts that were completed.
+    pub fn completed_attempts(&self) -> impl Iterator<Item = &Attempt> {
+        self.attempts
+            .iter()
+            .filter(|attempt| attempt.is_completed())
+    }
+
+    /// Final time of the personal best.
+    pub fn personal_best(&self) -> Option<Time> {
+        self.segments
+            .last()
+            .map(|segment| segment.personal_best)
+            .filter(|time| !time.is_empty())
+    }
+
+    /// The attempt that set the personal best, if it is in the history.
+    pub fn personal_best_attempt(&self) -> Option<&Attempt> {
+        let pb = self.personal_best()?;
+        self.completed_attempts().find(|attempt| {
+            same_time(attempt.time.real_time, pb.real_time)
+                && same_time(attempt.time.game_time, pb.game_time)
+        })
+    }
+
+    /// The final time and the attempt (if known) for `selection`.
+    pub fn select(&self, selection: Selection) -> Result<(Time, Option<&Attempt>), LiveSplitError> {
+        match selection {
+            Selection::PersonalBest => {
+                let time = self.personal_best().ok_or(LiveSplitError::NoTime)?;
+                Ok((time, self.personal_best_attempt()))
+            }
+            Selection::Attempt(id) => {
+                let attempt = self.attempt(id).ok_or(LiveSplitError::UnknownAttempt(id))?;
+                if !attempt.is_completed() {
+                    return Err(LiveSplitError::NoTime);
+                }
+                Ok((attempt.time, Some(attempt)))
+            }
+        }
+    }
+
+    /// Create a [`CreateRunBuilder`] for submitting `selection` to
+    /// `category`.
+    ///
+    /// The times, date and emulator flag are filled in from the splits. The
+    /// real time is submitted as `realtime` and the game time as `ingame`.
+    ///
+    /// The platform, region and variables of [`Metadata`] are names, not
+    /// `ID`s, and are not applied. Resolve the variables with
+    /// [`Resolver::variable`] and [`Resolver::value`] and set them on the
+    /// builder.
+    pub fn run_builder<'a, T>(
+        &self,
+        category: T,
+        selection: Selection,
+    ) -> Result<CreateRunBuilder<'a>, LiveSplitError>
+    where
+        T: Into<CategoryId<'a>>,
+    {
+        let (time, attempt) = self.select(selection)?;
+
+        let mut builder = CreateRun::buider();
+        builder
+            .category(category)
+            .emulated(self.metadata.uses_emulator.unwrap_or(false));
+        if let Some(real_time) = time.real_time {
+            builder.realtime(real_time);
+        }
+        if let Some(game_time) = time.game_time {
+            builder.ingame(game_time);
+        }
+        if let Some(date) = attempt.and_then(Attempt::date) {
+            builder.date(date);
+        }
+        Ok(builder)
+    }
+
+    /// Resolve the game and category names into IDs.
+    pub fn resolve<C>(
+        &self,
+        resolver: &mut Resolver<'_, C>,
+    ) -> Result<(GameId<'static>, CategoryId<'static>), ResolveError<C::Error>>
+    where
+        C: Client,
+    {
+        let game = resolver.game_by_name(&self.game_name)?;
+        let category = resolver.category(&game, &self.category_name)?;
+        Ok((game, category))
+    }
+
+    /// Resolve the game and category names into IDs asynchronously.
+    pub async fn resolve_async<C>(
+        &self,
+        resolver: &mut Resolver<'_, C>,
+    ) -> Result<(GameId<'static>, CategoryId<'static>), ResolveError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let game = resolver.game_by_name_async(&self.game_name).await?;
+        let category = resolver.category_async(&game, &self.category_name).await?;
+        Ok((game, category))
+    }
+}
+
+impl FromStr for Splits {
+    type Err = LiveSplitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl Attempt {
+    /// If the attempt was completed, rather than reset.
+    pub fn is_completed(&self) -> bool {
+        !self.time.is_empty()
+    }
+
+    /// The date the attempt was started, formatted as `YYYY-MM-DD`.
+    pub fn date(&self) -> Option<String> {
+        let started = self.started.as_deref()?;
+        let date = started.split_whitespace().next()?;
+        let mut parts = date.split('/');
+        let (month, day, year) = (parts.next()?, parts.next()?, parts.next()?);
+        let (month, day, year): (u32, u32, u32) =
+            (month.parse().ok()?, day.parse().ok()?, year.parse().ok()?);
+        Some(format!("{year:04}-{month:02}-{day:02}"))
+    }
+}
+
+impl Time {
+    /// If neither time is set.
+    pub fn is_empty(&self) -> bool {
+        self.real_time.is_none() && self.game_time.is_none()
+    }
+}
+
+/// Parse a LiveSplit time (`[-][d.]hh:mm:ss[.fffffff]`) into seconds.
+pub fn parse_time(time: &str) -> Result<f64, LiveSplitError> {
+    let invalid = || LiveSplitError::Time(time.to_owned());
+
+    let trimmed = time.trim();
+    let (negative, trimmed) = match trimmed.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, trimmed),
+    };
+    let mut parts = trimmed.rsplitn(3, ':');
+    let (seconds, minutes, hours) = (
+        parts.next().ok_or_else(invalid)?,
+        parts.next().ok_or_else(invalid)?,
+        parts.next().ok_or_else(invalid)?,
+    );
+    let (days, hours) = match hours.split_once('.') {
+        Some((days, hours)) => (days, hours),
+        None => ("0", hours),
+    };
+
+    let days: u64 = days.parse().map_err(|_| invalid())?;
+    let hours: u64 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u64 = minutes.parse().map_err(|_| invalid())?;
+    let seconds: f64 = seconds.parse().map_err(|_| invalid())?;
+    if minutes >= 60 || !(0.0..60.0).contains(&seconds) {
+        return Err(invalid());
+    }
+
+    let minutes = days
+        .checked_mul(24)
+        .and_then(|hours_in_days| hours_in_days.checked_add(hours))
+        .and_then(|hours| hours.checked_mul(60))
+        .and_then(|minutes_in_hours| minutes_in_hours.checked_add(minutes))
+        .ok_or_else(invalid)?;
+    let total = minutes as f64 * 60.0 + seconds;
+    Ok(if negative { -total } else { total })
+}
+
+/// If both times are missing, or both are set and equal up to rounding.
+fn same_time(a: Option<f64>, b: Option<f64>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => (a - b).abs() < 1e-6,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+fn child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|child| child.has_tag_name(name))
+}
+
+fn children<'a, 'i: 'a>(
+    node: Node<'a, 'i>,
+    name: &'static str,
+) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children()
+        .filter(move |child| child.has_tag_name(name))
+}
+
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    child(node, name).map(|child| child.text().unwrap_or_default().trim())
+}
+
+fn parse_metadata(node: Node<'_, '_>) -> Metadata {
+    let non_empty = |text: &str| (!text.is_empty()).then(|| text.to_owned());
+    let platform = child(node, "Platform");
+
+    Metadata {
+        platform: child_text(node, "Platform").and_then(non_empty),
+        uses_emulator: platform
+            .and_then(|platform| platform.attribute("usesEmulator"))
+            .map(|value| value.eq_ignore_ascii_case("true")),
+        region: child_text(node, "Region").and_then(non_empty),
+        variables: child(node, "Variables")
+            .into_iter()
+            .flat_map(|variables| children(variables, "Variable"))
+            .filter_map(|variable| {
+                let name = variable.attribute("name")?;
+                let value = variable.text().unwrap_or_default().trim();
+                Some((name.to_owned(), value.to_owned()))
+            })
+            .collect(),
+    }
+}
+
+fn parse_attempt(node: Node<'_, '_>) -> Result<Attempt, LiveSplitError> {
+    Ok(Attempt {
+        id: parse_id(node)?,
+        started: node.attribute("started").map(str::to_owned),
+        ended: node.attribute("ended").map(str::to_owned),
+        time: parse_times(node)?,
+    })
+}
+
+fn parse_segment(node: Node<'_, '_>) -> Result<Segment, LiveSplitError> {
+    let personal_best = child(node, "SplitTimes")
+        .into_iter()
+        .flat_map(|times| children(times, "SplitTime"))
+        .find(|time| time.attribute("name") == Some(PERSONAL_BEST))
+        .map(parse_times)
+        .transpose()?
+        .unwrap_or_default();
+    let best_segment = child(node, "BestSegmentTime")
+        .map(parse_times)
+        .transpose()?
+        .unwrap_or_default();
+    let history = child(node, "SegmentHistory")
+        .into_iter()
+        .flat_map(|history| children(history, "Time"))
+        .map(|time| Ok((parse_id(time)?, parse_times(time)?)))
+        .collect::<Result<_, LiveSplitError>>()?;
+
+    Ok(Segment {
+        name: child_text(node, "Name").unwrap_or_default().to_owned(),
+        personal_best,
+        best_segment,
+        history,
+    })
+}
+
+fn parse_id(node: Node<'_, '_>) -> Result<i64, LiveSplitError> {
+    let id = node.attribute("id").unwrap_or_default();
+    id.parse()
+        .map_err(|_| LiveSplitError::AttemptId(id.to_owned()))
+}
+
+fn parse_times(node: Node<'_, '_>) -> Result<Time, LiveSplitError> {
+    let parse = |name| {
+        child_text(node, name)
+            .filter(|text| !text.is_empty())
+            .map(parse_time)
+            .transpose()
+    };
+    Ok(Time {
+        real_time: parse("RealTime")?,
+        game_time: parse("GameTime")?,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SPLITS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Run version="1.7.0">
+  <GameIcon />
+  <GameName>Super Mario Sunshine</GameName>
+  <CategoryName>Any%</CategoryName>
+  <Metadata>
+    <Run id="" />
+    <Platform usesEmulator="True">GameCube</Platform>
+    <Region />
+    <Variables>
+      <Variable name="Version">JP</Variable>
+    </Variables>
+  </Metadata>
+  <Offset>00:00:00</Offset>
+  <AttemptCount>3</AttemptCount>
+  <AttemptHistory>
+    <Attempt id="1" started="10/18/2020 12:00:00" isStartedSynced="True" ended="10/18/2020 13:11:03" isEndedSynced="True">
+      <RealTime>01:11:03.5000000</RealTime>
+    </Attempt>
+    <Attempt id="2" started="11/02/2020 08:00:00" isStartedSynced="True" ended="11/02/2020 08:01:00" isEndedSynced="True" />
+    <Attempt id="3" started="12/24/2020 18:30:00" isStartedSynced="True" ended="12/24/2020 19:40:00" isEndedSynced="True">
+      <RealTime>01:10:00.2500000</RealTime>
+      <GameTime>01:05:00</GameTime>
+    </Attempt>
+  </AttemptHistory>
+  <Segments>
+    <Segment>
+      <Name>Bianco Hills</Name>
+      <Icon />
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <RealTime>00:30:00</RealTime>
+        </SplitTime>
+      </SplitTimes>
+      <BestSegmentTime>
+        <RealTime>00:29:00</RealTime>
+      </BestSegmentTime>
+      <SegmentHistory>
+        <Time id="1"><RealTime>00:31:00</RealTime></Time>
+        <Time id="3"><RealTime>00:30:00</RealTime></Time>
+      </SegmentHistory>
+    </Segment>
+    <Segment>
+      <Name>Corona Mountain</Name>
+      <Icon />
+      <SplitTimes>
+        <SplitTime name="Personal Best">
+          <RealTime>01:10:00.2500000</RealTime>
+          <GameTime>01:05:00</GameTime>
+        </SplitTime>
+      </SplitTimes>
+      <BestSegmentTime />
+      <SegmentHistory />
+    </Segment>
+  </Segments>
+</Run>"#;
+
+    #[test]
+    fn parse_splits() {
+        let splits = Splits::parse(SPLITS).unwrap();
+        assert_eq!(splits.game_name, "Super Mario Sunshine");
+        assert_eq!(splits.category_name, "Any%");
+        assert_eq!(splits.metadata.uses_emulator, Some(true));
+        assert_eq!(splits.metadata.region, None);
+        assert_eq!(
+            splits.metadata.variables,
+            [("Version".to_owned(), "JP".to_owned())]
+        );
+        assert_eq!(splits.attempts.len(), 3);
+        assert_eq!(splits.completed_attempts().count(), 2);
+        assert_eq!(splits.segments[0].history[&1].real_time, Some(1860.0));
+        assert_eq!(splits.personal_best_attempt().map(|a| a.id), Some(3));
+    }
+
+    #[test]
+    fn builder_from_attempt() {
+        let splits = Splits::parse(SPLITS).unwrap();
+        let run = splits
+            .run_builder("cat", Selection::Attempt(1))
+            .unwrap()
+            .build()
+            .unwrap();
+        let body = serde_json::to_value(&run).unwrap();
+        assert_eq!(body["date"], "2020-10-18");
+        assert_eq!(body["emulated"], true);
+        assert_eq!(body["times"]["realtime"], 4263.5);
+
+        assert!(matches!(
+            splits.run_builder("cat", Selection::Attempt(2)),
+            Err(LiveSplitError::NoTime)
+        ));
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("00:01:02.5").unwrap(), 62.5);
+        assert_eq!(parse_time("1.00:00:01").unwrap(), 86401.0);
+        assert_eq!(parse_time("-00:00:01").unwrap(), -1.0);
+        assert!(parse_time("01:02").is_err());
+        assert!(parse_time("18446744073709551615.00:00:00").is_err());
+        assert!(parse_time("18446744073709551615:00:00").is_err());
+    }
+}
@@ -0,0 +1,168 @@
+//! A client answering requests from a closure, for tests.
+use std::{convert::Infallible, sync::Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{request::Builder as RequestBuilder, Method, Response, StatusCode};
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::api::{ApiError, AsyncClient, Client, RestClient};
+
+const BASE_URL: &str = "https://www.speedrun.com/api/v1/";
+
+/// Page size of the API when `max` is not set.
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+type Handler = dyn Fn(&Request) -> Result<Value, StatusCode> + Send + Sync;
+
+/// A request received by a [`MockClient`].
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    pub(crate) method: Method,
+    pub(crate) url: Url,
+    pub(crate) body: Vec<u8>,
+}
+
+impl Request {
+    /// Path of the request, relative to the API base URL.
+    pub(crate) fn path(&self) -> &str {
+        self.url.path().trim_start_matches("/api/v1/")
+    }
+
+    /// All values of the query parameter `key`.
+    pub(crate) fn query_all(&self, key: &str) -> Vec<String> {
+        self.url
+            .query_pairs()
+            .filter(|(name, _)| name == key)
+            .map(|(_, value)| value.into_owned())
+            .collect()
+    }
+
+    /// The value of the query parameter `key`, if it is set once.
+    pub(crate) fn query(&self, key: &str) -> Option<String> {
+        match self.query_all(key).as_slice() {
+            [value] => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// The value of the paging parameter `key`, or `default`.
+    pub(crate) fn query_usize(&self, key: &str, default: usize) -> usize {
+        self.query(key)
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    }
+
+    /// The JSON body of the request.
+    pub(crate) fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or_default()
+    }
+}
+
+/// A client answering every request with the `data` returned by a handler,
+/// or `404 Not Found` when the handler returns `None`. Responses to paged
+/// requests also describe the page.
+pub(crate) struct MockClient {
+    handler: Box<Handler>,
+    requests: Mutex<Vec<Request>>,
+}
+
+impl MockClient {
+    pub(crate) fn new<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Option<Value> + Send + Sync + 'static,
+    {
+        Self::with_status(move |request| handler(request).ok_or(StatusCode::NOT_FOUND))
+    }
+
+    /// A client answering with the error status returned by `handler`
+    /// instead of `404 Not Found`.
+    pub(crate) fn with_status<F>(handler: F) -> Self
+    where
+        F: Fn(&Request) -> Result<Value, StatusCode> + Send + Sync + 'static,
+    {
+        Self {
+            handler: Box::new(handler),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Requests received so far.
+    pub(crate) fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, request: RequestBuilder, body: Vec<u8>) -> Response<Bytes> {
+        let request = Request {
+            method: request.method_ref().cloned().unwrap_or_default(),
+            url: Url::parse(&request.uri_ref().expect("request has a URI").to_string())
+                .expect("request URI is a URL"),
+            body,
+        };
+        let (status, value) = match (self.handler)(&request) {
+            Ok(data) if request.query("offset").is_some() => {
+                let pagination = json!({
+                    "offset": request.query_usize("offset", 0),
+                    "max": request.query_usize("max", DEFAULT_PAGE_SIZE),
+                    "size": data.as_array().map_or(0, Vec::len),
+                    "links": [],
+                });
+                (
+                    StatusCode::OK,
+                    json!({ "data": data, "pagination": pagination }),
+                )
+            }
+            Ok(data) => (StatusCode::OK, json!({ "data": data })),
+            Err(status) => (
+                status,
+                json!({ "status": status.as_u16(), "message": status.canonical_reason() }),
+            ),
+        };
+        self.requests.lock().unwrap().push(request);
+        Response::builder()
+            .status(status)
+            .body(Bytes::from(value.to_string()))
+            .expect("valid response")
+    }
+}
+
+impl RestClient for MockClient {
+    type Error = Infallible;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        Ok(Url::parse(BASE_URL)?.join(endpoint.trim_start_matches('/'))?)
+    }
+
+    fn has_api_key(&self) -> bool {
+        true
+    }
+}
+
+impl Client for MockClient {
+    fn rest(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        Ok(self.respond(request, body))
+    }
+}
+
+#[async_trait]
+impl AsyncClient for MockClient {
+    async fn rest_async(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<Response<Bytes>, ApiError<Self::Error>> {
+        Ok(self.respond(request, body))
+    }
+}
+
+/// The slice of `items` requested by the paging parameters of `request`.
+pub(crate) fn page(request: &Request, items: &[Value]) -> Value {
+    let offset = request.query_usize("offset", 0).min(items.len());
+    let max = request.query_usize("max", DEFAULT_PAGE_SIZE);
+    Value::Array(items[offset..].iter().take(max).cloned().collect())
+}
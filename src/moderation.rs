@@ -0,0 +1,441 @@
+//! # Moderation
+//!
+//! Helpers for game moderators working through the queue of runs waiting for
+//! verification.
+//!
+//! A [`Moderator`] lists the pending runs of every game moderated by the
+//! authenticated user, verifies and rejects runs (optionally using rejection
+//! reason templates) and keeps a log of every action taken.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{moderation::Moderator, SpeedrunApiBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().api_key("KEY").build_async()?;
+//! let mut moderator = Moderator::new(&client);
+//! moderator.add_template("no-video", "{players}: a video is required for {category}.");
+//!
+//! for pending in moderator.queue_async().await? {
+//!     if pending.run.videos.is_none() {
+//!         moderator.reject_with_template_async(&pending, "no-video").await?;
+//!     }
+//! }
+//! for action in moderator.log() {
+//!     println!("{action}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Many runs can be verified or rejected at once with a [`BatchUpdate`], and
+//! [`add_player`], [`remove_player`] and [`replace_player`] change the players
+//! of a run.
+use std::{collections::BTreeMap, fmt::Display, time::SystemTime};
+
+use futures::TryStreamExt;
+use serde::{de::Error as _, Deserialize, Deserializer};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        games::{GameId, Games},
+        profile::Profile,
+        runs::{NewStatus, RunEmbeds, RunId, RunStatus, Runs, RunsSorting, UpdateRunStatus},
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, PagedEndpointExt, Query,
+    },
+    render::format_time,
+    text::substitute,
+    types::{self, Embedded},
+};
+
+mod batch;
+mod players;
+
+pub use batch::{BatchEntry, BatchReport, BatchUpdate, BatchUpdateBuilder};
+pub use players::{
+    add_player, add_player_async, remove_player, remove_player_async, replace_player,
+    replace_player_async, PlayersError,
+};
+
+/// Errors that occur when moderating runs.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ModerationError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// No rejection reason template with this name was added.
+    #[error("unknown rejection reason template `{0}`")]
+    UnknownTemplate(String),
+}
+
+/// A run waiting for verification, with its game, category and players.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingRun {
+    /// The run.
+    pub run: types::Run<'static>,
+    /// The game of the run.
+    pub game: types::Game<'static>,
+    /// The category of the run.
+    pub category: types::Category<'static>,
+    /// The players of the run.
+    pub players: Vec<RunPlayer>,
+}
+
+/// A player of a [`PendingRun`].
+pub type RunPlayer = types::EmbeddedPlayer<'static>;
+
+/// An action taken by a [`Moderator`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Action {
+    /// `ID` of the run.
+    pub run: RunId<'static>,
+    /// What was done.
+    pub kind: ActionKind,
+    /// When the action was taken.
+    pub at: SystemTime,
+}
+
+/// The kind of an [`Action`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActionKind {
+    /// The run was verified.
+    Verified,
+    /// The run was rejected.
+    Rejected {
+        /// The reason given for the rejection.
+        reason: String,
+    },
+}
+
+/// Works through the moderation queue, keeping a log of actions taken.
+///
+/// Methods ending in `_async` use an [`AsyncClient`], the others a [`Client`].
+#[derive(Debug)]
+pub struct Moderator<'c, C> {
+    client: &'c C,
+    templates: BTreeMap<String, String>,
+    log: Vec<Action>,
+}
+
+impl<'c, C> Moderator<'c, C> {
+    /// Create a new moderator using `client`, which must have an API key.
+    pub fn new(client: &'c C) -> Self {
+        Self {
+            client,
+            templates: BTreeMap::new(),
+            log: Vec::new(),
+        }
+    }
+
+    /// Add a rejection reason template.
+    ///
+    /// The placeholders `{game}`, `{category}`, `{players}`, `{time}` and
+    /// `{weblink}` are replaced with the details of the rejected run.
+    pub fn add_template<N, T>(&mut self, name: N, template: T) -> &mut Self
+    where
+        N: Into<String>,
+        T: Into<String>,
+    {
+        self.templates.insert(name.into(), template.into());
+        self
+    }
+
+    /// The rejection reason templates, by name.
+    pub fn templates(&self) -> &BTreeMap<String, String> {
+        &self.templates
+    }
+
+    /// The rejection reason for `run` using the template `name`.
+    pub fn render_reason(&self, name: &str, run: &PendingRun) -> Option<String> {
+        self.templates
+            .get(name)
+            .map(|template| render(template, run))
+    }
+
+    /// Actions taken so far, oldest first.
+    pub fn log(&self) -> &[Action] {
+        &self.log
+    }
+
+    /// Take the actions logged so far, clearing the log.
+    pub fn take_log(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.log)
+    }
+
+    fn record(&mut self, run: &RunId<'_>, kind: ActionKind) {
+        self.log.push(Action {
+            run: RunId::new(run.to_string()),
+            kind,
+            at: SystemTime::now(),
+        });
+    }
+}
+
+impl<C> Moderator<'_, C>
+where
+    C: Client,
+{
+    /// Games moderated by the authenticated user.
+    pub fn moderated_games(&self) -> Result<Vec<types::Game<'static>>, ApiError<C::Error>> {
+        let user: types::User<'static> = profile().query(self.client)?;
+        moderated_games_endpoint(&user).iter(self.client).collect()
+    }
+
+    /// Runs of `game` waiting for verification, oldest submission first.
+    pub fn pending_runs(&self, game: &GameId<'_>) -> Result<Vec<PendingRun>, ApiError<C::Error>> {
+        pending_runs_endpoint(game).iter(self.client).collect()
+    }
+
+    /// Runs waiting for verification in all games moderated by the
+    /// authenticated user, oldest submission first.
+    pub fn queue(&self) -> Result<Vec<PendingRun>, ApiError<C::Error>> {
+        let mut queue = Vec::new();
+        for game in self.moderated_games()? {
+            queue.extend(self.pending_runs(&game.id)?);
+        }
+        sort_queue(&mut queue);
+        Ok(queue)
+    }
+
+    /// Verify `run`.
+    pub fn verify(&mut self, run: &RunId<'_>) -> Result<(), ApiError<C::Error>> {
+        let _: Value = update_status(run, NewStatus::Verified).query(self.client)?;
+        self.record(run, ActionKind::Verified);
+        Ok(())
+    }
+
+    /// Reject `run` with `reason`.
+    pub fn reject(&mut self, run: &RunId<'_>, reason: &str) -> Result<(), ApiError<C::Error>> {
+        let status = NewStatus::Rejected {
+            reason: reason.to_owned(),
+        };
+        let _: Value = update_status(run, status).query(self.client)?;
+        self.record(
+            run,
+            ActionKind::Rejected {
+                reason: reason.to_owned(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Reject `run` with the reason rendered from the template `name`.
+    pub fn reject_with_template(
+        &mut self,
+        run: &PendingRun,
+        name: &str,
+    ) -> Result<(), ModerationError<C::Error>> {
+        let reason = self
+            .render_reason(name, run)
+            .ok_or_else(|| ModerationError::UnknownTemplate(name.to_owned()))?;
+        Ok(self.reject(&run.run.id, &reason)?)
+    }
+}
+
+impl<C> Moderator<'_, C>
+where
+    C: AsyncClient + Sync,
+{
+    /// Games moderated by the authenticated user.
+    pub async fn moderated_games_async(
+        &self,
+    ) -> Result<Vec<types::Game<'static>>, ApiError<C::Error>> {
+        let user: types::User<'static> = profile().query_async(self.client).await?;
+        moderated_games_endpoint(&user)
+            .stream(self.client)
+            .try_collect()
+            .await
+    }
+
+    /// Runs of `game` waiting for verification, oldest submission first.
+    pub async fn pending_runs_async(
+        &self,
+        game: &GameId<'_>,
+    ) -> Result<Vec<PendingRun>, ApiError<C::Error>> {
+        pending_runs_endpoint(game)
+            .stream(self.client)
+            .try_collect()
+            .await
+    }
+
+    /// Runs waiting for verification in all games moderated by the
+    /// authenticated user, oldest submission first.
+    pub async fn queue_async(&self) -> Result<Vec<PendingRun>, ApiError<C::Error>> {
+        let mut queue = Vec::new();
+        for game in self.moderated_games_async().await? {
+            queue.extend(self.pending_runs_async(&game.id).await?);
+        }
+        sort_queue(&mut queue);
+        Ok(queue)
+    }
+
+    /// Verify `run`.
+    pub async fn verify_async(&mut self, run: &RunId<'_>) -> Result<(), ApiError<C::Error>> {
+        let _: Value = update_status(run, NewStatus::Verified)
+            .query_async(self.client)
+            .await?;
+        self.record(run, ActionKind::Verified);
+        Ok(())
+    }
+
+    /// Reject `run` with `reason`.
+    pub async fn reject_async(
+        &mut self,
+        run: &RunId<'_>,
+        reason: &str,
+    ) -> Result<(), ApiError<C::Error>> {
+        let status = NewStatus::Rejected {
+            reason: reason.to_owned(),
+        };
+        let _: Value = update_status(run, status).query_async(self.client).await?;
+        self.record(
+            run,
+            ActionKind::Rejected {
+                reason: reason.to_owned(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Reject `run` with the reason rendered from the template `name`.
+    pub async fn reject_with_template_async(
+        &mut self,
+        run: &PendingRun,
+        name: &str,
+    ) -> Result<(), ModerationError<C::Error>> {
+        let reason = self
+            .render_reason(name, run)
+            .ok_or_else(|| ModerationError::UnknownTemplate(name.to_owned()))?;
+        Ok(self.reject_async(&run.run.id, &reason).await?)
+    }
+}
+
+impl<'de> Deserialize<'de> for PendingRun {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Embedded { data: run, embeds } =
+            Embedded::<types::Run<'static>>::deserialize(deserializer)?;
+        Ok(Self {
+            run,
+            game: embeds.game.ok_or_else(|| D::Error::missing_field("game"))?,
+            category: embeds
+                .category
+                .ok_or_else(|| D::Error::missing_field("category"))?,
+            players: embeds.players,
+        })
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ActionKind::Verified => write!(f, "verified {}", self.run),
+            ActionKind::Rejected { reason } => write!(f, "rejected {}: {}", self.run, reason),
+        }
+    }
+}
+
+fn profile() -> Profile {
+    Profile::builder()
+        .build()
+        .expect("all required fields are set")
+}
+
+fn moderated_games_endpoint<'a>(user: &'a types::User<'_>) -> Games<'a> {
+    Games::builder()
+        .moderator(user.id.to_string())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn pending_runs_endpoint<'a>(game: &GameId<'a>) -> Runs<'a> {
+    Runs::builder()
+        .game(game.clone())
+        .status(RunStatus::New)
+        .orderby(RunsSorting::Submitted)
+        .direction(Direction::Asc)
+        .embeds([RunEmbeds::Game, RunEmbeds::Category, RunEmbeds::Players].into_iter())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn update_status<'a>(run: &RunId<'a>, status: NewStatus) -> UpdateRunStatus<'a> {
+    UpdateRunStatus::builder()
+        .id(run.clone())
+        .status(status)
+        .build()
+        .expect("all required fields are set")
+}
+
+/// Sort by submission date, oldest first (the API returns RFC 3339 dates).
+fn sort_queue(queue: &mut [PendingRun]) {
+    queue.sort_by(|a, b| a.run.submitted.cmp(&b.run.submitted));
+}
+
+fn render(template: &str, run: &PendingRun) -> String {
+    substitute(template, |name| match name {
+        "game" => Some(run.game.names.international.clone()),
+        "category" => Some(run.category.name.clone()),
+        "players" => Some(
+            run.players
+                .iter()
+                .map(RunPlayer::name)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        "time" => Some(format_time(run.run.times.primary_t, None)),
+        "weblink" => Some(run.run.weblink.clone()),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    /// A pending run of `player` with the embeds requested by the queue.
+    fn pending(player: &str) -> PendingRun {
+        let mut run = fixtures::run_json("r", "u", 83.0, "2021-01-01");
+        run["weblink"] = json!("https://www.speedrun.com/game/run/r");
+        run["status"] = json!({ "status": "new" });
+        run["game"] = json!({ "data": fixtures::game_json() });
+        run["category"] = json!({ "data": fixtures::category_json("per-game") });
+        run["players"] = json!({ "data": [{ "rel": "guest", "name": player, "links": [] }] });
+        serde_json::from_value(run).unwrap()
+    }
+
+    #[test]
+    fn pending_run_with_embeds() {
+        let pending = pending("someone");
+        assert_eq!(pending.run.game, GameId::new("game"));
+        assert_eq!(pending.game.names.international, "Game");
+        assert_eq!(pending.category.name, "Any%");
+        assert_eq!(pending.players[0].name(), "someone");
+        assert!(matches!(
+            &pending.run.players[..],
+            [types::Player::Guest { name, .. }] if name == "someone"
+        ));
+    }
+
+    #[test]
+    fn render_substitutes_once() {
+        assert_eq!(
+            render(
+                "{players}: {category} in {time} ({weblink}) {unknown}",
+                &pending("{weblink}")
+            ),
+            "{weblink}: Any% in 1:23 (https://www.speedrun.com/game/run/r) {unknown}"
+        );
+    }
+}
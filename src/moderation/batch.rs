@@ -0,0 +1,503 @@
+use std::{
+    future::Future,
+    io::{self, Write},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use futures::{stream, StreamExt};
+use serde_json::Value;
+
+use crate::{
+    api::{
+        runs::{NewStatus, RunId, UpdateRunStatus},
+        utils::ResponseError,
+        ApiError, AsyncClient, AsyncQuery, Client, Query,
+    },
+    export::csv_field,
+};
+
+/// Updates the status of many runs, with bounded concurrency, rate limiting
+/// and retries of transient failures.
+///
+/// The default rate limit matches the 100 requests per minute allowed by
+/// speedrun.com.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into))]
+pub struct BatchUpdate<'a> {
+    #[builder(setter(name = "_updates"), private, default)]
+    updates: Vec<(RunId<'a>, NewStatus)>,
+    #[doc = r"Maximum number of requests in flight (default: 4)."]
+    #[builder(default = "4")]
+    concurrency: usize,
+    #[doc = r"Maximum number of requests started per minute (default: 100)."]
+    #[builder(default = "100")]
+    requests_per_minute: u32,
+    #[doc = r"Maximum number of attempts for each run, including the first one (default: 3)."]
+    #[builder(default = "3")]
+    max_attempts: u32,
+    #[doc = r"Delay before the first retry, doubled on every following retry (default: 1s)."]
+    #[builder(default = "Duration::from_secs(1)")]
+    retry_delay: Duration,
+}
+
+/// Outcome of a single update in a [`BatchReport`].
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    /// `ID` of the run.
+    pub run: RunId<'static>,
+    /// The requested status.
+    pub status: NewStatus,
+    /// Number of attempts made.
+    pub attempts: u32,
+    /// The error of the last attempt, if the update failed.
+    pub error: Option<String>,
+}
+
+/// Per-run results of a [`BatchUpdate`], in the order the updates were added.
+#[derive(Debug, Clone, Default)]
+pub struct BatchReport {
+    /// Result of each update.
+    pub entries: Vec<BatchEntry>,
+}
+
+impl BatchUpdate<'_> {
+    /// Create a builder for a batch update.
+    pub fn builder<'a>() -> BatchUpdateBuilder<'a> {
+        BatchUpdateBuilder::default()
+    }
+
+    /// Execute the updates using `client`.
+    ///
+    /// Requests are sent from up to `concurrency` threads.
+    pub fn execute<C>(&self, client: &C) -> BatchReport
+    where
+        C: Client + Sync,
+    {
+        let limiter = RateLimiter::new(self.requests_per_minute);
+        let pending = Mutex::new(self.updates.iter().enumerate());
+        let results = Mutex::new(Vec::with_capacity(self.updates.len()));
+
+        std::thread::scope(|scope| {
+            for _ in 0..self.concurrency.clamp(1, self.updates.len().max(1)) {
+                scope.spawn(|| loop {
+                    let next = pending.lock().expect("lock is not poisoned").next();
+                    let Some((index, (run, status))) = next else {
+                        break;
+                    };
+
+                    let endpoint = update_status(run, status);
+                    let mut attempts = 0;
+                    let result = loop {
+                        attempts += 1;
+                        std::thread::sleep(limiter.reserve());
+                        let result: Result<Value, _> = endpoint.query(client);
+                        match result {
+                            Err(err) if attempts < self.max_attempts && is_transient(&err) => {
+                                std::thread::sleep(self.backoff(attempts));
+                            }
+                            result => break result.map(|_| ()),
+                        }
+                    };
+                    let entry = entry(run, status, attempts, result);
+                    results
+                        .lock()
+                        .expect("lock is not poisoned")
+                        .push((index, entry));
+                });
+            }
+        });
+
+        report(results.into_inner().expect("lock is not poisoned"))
+    }
+
+    /// Execute the updates asynchronously using `client`.
+    ///
+    /// Up to `concurrency` requests are in flight at the same time. Requires
+    /// the `tokio` feature, see [`BatchUpdate::execute_async_with`] otherwise.
+    #[cfg(feature = "tokio")]
+    pub async fn execute_async<C>(&self, client: &C) -> BatchReport
+    where
+        C: AsyncClient + Sync,
+    {
+        self.execute_async_with(client, tokio::time::sleep).await
+    }
+
+    /// Execute the updates asynchronously using `client`, waiting with
+    /// `sleep` for the rate limit and between retries.
+    ///
+    /// Up to `concurrency` requests are in flight at the same time.
+    pub async fn execute_async_with<C, F, S>(&self, client: &C, sleep: F) -> BatchReport
+    where
+        C: AsyncClient + Sync,
+        F: Fn(Duration) -> S,
+        S: Future<Output = ()>,
+    {
+        let sleep = &sleep;
+        let limiter = RateLimiter::new(self.requests_per_minute);
+        let limiter = &limiter;
+
+        let results = stream::iter(self.updates.iter().enumerate())
+            .map(|(index, (run, status))| async move {
+                let endpoint = update_status(run, status);
+                let mut attempts = 0;
+                let result = loop {
+                    attempts += 1;
+                    sleep(limiter.reserve()).await;
+                    let result: Result<Value, _> = endpoint.query_async(client).await;
+                    match result {
+                        Err(err) if attempts < self.max_attempts && is_transient(&err) => {
+                            sleep(self.backoff(attempts)).await;
+                        }
+                        result => break result.map(|_| ()),
+                    }
+                };
+                (index, entry(run, status, attempts, result))
+            })
+            .buffer_unordered(self.concurrency.max(1))
+            .collect()
+            .await;
+
+        report(results)
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.retry_delay * 2u32.saturating_pow(attempts - 1)
+    }
+}
+
+impl<'a> BatchUpdateBuilder<'a> {
+    /// Set the status of `run`.
+    pub fn update<R>(&mut self, run: R, status: NewStatus) -> &mut Self
+    where
+        R: Into<RunId<'a>>,
+    {
+        self.updates
+            .get_or_insert_with(Vec::new)
+            .push((run.into(), status));
+        self
+    }
+
+    /// Set the status of multiple runs.
+    pub fn updates<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: Iterator<Item = (RunId<'a>, NewStatus)>,
+    {
+        self.updates.get_or_insert_with(Vec::new).extend(iter);
+        self
+    }
+
+    /// Verify `run`.
+    pub fn verify<R>(&mut self, run: R) -> &mut Self
+    where
+        R: Into<RunId<'a>>,
+    {
+        self.update(run, NewStatus::Verified)
+    }
+
+    /// Reject `run` with `reason`.
+    pub fn reject<R, S>(&mut self, run: R, reason: S) -> &mut Self
+    where
+        R: Into<RunId<'a>>,
+        S: Into<String>,
+    {
+        self.update(
+            run,
+            NewStatus::Rejected {
+                reason: reason.into(),
+            },
+        )
+    }
+}
+
+impl BatchEntry {
+    /// If the update succeeded.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl BatchReport {
+    /// Updates that succeeded.
+    pub fn succeeded(&self) -> impl Iterator<Item = &BatchEntry> {
+        self.entries.iter().filter(|entry| entry.is_success())
+    }
+
+    /// Updates that failed.
+    pub fn failed(&self) -> impl Iterator<Item = &BatchEntry> {
+        self.entries.iter().filter(|entry| !entry.is_success())
+    }
+
+    /// Write the report as CSV, with a header row.
+    ///
+    /// Columns: `run`, `status`, `reason`, `result`, `attempts`, `error`.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "run,status,reason,result,attempts,error")?;
+        for entry in &self.entries {
+            let (status, reason) = match &entry.status {
+                NewStatus::Verified => ("verified", ""),
+                NewStatus::Rejected { reason } => ("rejected", reason.as_str()),
+            };
+            let result = if entry.is_success() { "ok" } else { "failed" };
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                csv_field(&entry.run.to_string()),
+                status,
+                csv_field(reason),
+                result,
+                entry.attempts,
+                csv_field(entry.error.as_deref().unwrap_or_default()),
+            )?;
+        }
+        writer.flush()
+    }
+}
+
+/// Spaces requests evenly to stay within a number of requests per minute.
+struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_minute: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(60) / requests_per_minute.max(1),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Reserve the next request slot, returning how long to wait for it.
+    fn reserve(&self) -> Duration {
+        let mut next = self.next.lock().expect("lock is not poisoned");
+        let now = Instant::now();
+        let slot = (*next).max(now);
+        *next = slot + self.interval;
+        slot - now
+    }
+}
+
+fn update_status<'a>(run: &RunId<'a>, status: &NewStatus) -> UpdateRunStatus<'a> {
+    UpdateRunStatus::builder()
+        .id(run.clone())
+        .status(status.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+/// If retrying the request may succeed: communication errors, rate limiting,
+/// server errors and non-JSON (e.g. proxy error page) responses.
+fn is_transient<E>(err: &ApiError<E>) -> bool
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match err {
+        ApiError::Client(_) => true,
+        ApiError::Response { source, .. } => match source {
+            ResponseError::HttpStatus { status, .. } => {
+                status.is_server_error() || *status == http::StatusCode::TOO_MANY_REQUESTS
+            }
+            ResponseError::Parse(_) => true,
+            ResponseError::DataType { .. } => false,
+        },
+        _ => false,
+    }
+}
+
+fn entry<E>(
+    run: &RunId<'_>,
+    status: &NewStatus,
+    attempts: u32,
+    result: Result<(), ApiError<E>>,
+) -> BatchEntry
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    BatchEntry {
+        run: RunId::new(run.to_string()),
+        status: status.clone(),
+        attempts,
+        error: result.err().map(|err| err.to_string()),
+    }
+}
+
+fn report(mut results: Vec<(usize, BatchEntry)>) -> BatchReport {
+    results.sort_by_key(|(index, _)| *index);
+    BatchReport {
+        entries: results.into_iter().map(|(_, entry)| entry).collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, convert::Infallible};
+
+    use http::StatusCode;
+    use serde_json::json;
+
+    use super::*;
+    use crate::mock::MockClient;
+
+    /// A client failing the status update of each run with the given
+    /// statuses, in order, before it succeeds.
+    fn client(failures: &[(&str, &[StatusCode])]) -> MockClient {
+        let failures: HashMap<String, Vec<StatusCode>> = failures
+            .iter()
+            .map(|(run, statuses)| (format!("runs/{run}/status"), statuses.to_vec()))
+            .collect();
+        let failures = Mutex::new(failures);
+        MockClient::with_status(move |request| {
+            let mut failures = failures.lock().unwrap();
+            match failures.get_mut(request.path()) {
+                Some(statuses) if !statuses.is_empty() => Err(statuses.remove(0)),
+                _ => Ok(json!({ "id": request.path() })),
+            }
+        })
+    }
+
+    fn batch() -> BatchUpdate<'static> {
+        BatchUpdate::builder()
+            .verify("a")
+            .verify("b")
+            .reject("c", "no video")
+            .verify("d")
+            .verify("e")
+            .concurrency(4usize)
+            .requests_per_minute(u32::MAX)
+            .retry_delay(Duration::ZERO)
+            .build()
+            .unwrap()
+    }
+
+    fn failures() -> Vec<(&'static str, &'static [StatusCode])> {
+        vec![
+            ("a", &[StatusCode::SERVICE_UNAVAILABLE]),
+            (
+                "b",
+                &[StatusCode::TOO_MANY_REQUESTS, StatusCode::BAD_GATEWAY],
+            ),
+            ("c", &[StatusCode::BAD_REQUEST]),
+            ("d", &[StatusCode::INTERNAL_SERVER_ERROR; 3]),
+        ]
+    }
+
+    fn check(report: &BatchReport) {
+        let outcomes: Vec<_> = report
+            .entries
+            .iter()
+            .map(|entry| (entry.run.to_string(), entry.attempts, entry.is_success()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("a".to_owned(), 2, true),
+                ("b".to_owned(), 3, true),
+                ("c".to_owned(), 1, false),
+                ("d".to_owned(), 3, false),
+                ("e".to_owned(), 1, true),
+            ]
+        );
+        assert!(matches!(
+            report.entries[2].status,
+            NewStatus::Rejected { ref reason } if reason == "no video"
+        ));
+    }
+
+    #[test]
+    fn retries_transient_failures() {
+        let client = client(&failures());
+        let report = batch().execute(&client);
+        check(&report);
+        assert_eq!(client.requests().len(), 10);
+    }
+
+    #[test]
+    fn retries_transient_failures_async() {
+        let client = client(&failures());
+        let report = futures::executor::block_on(
+            batch().execute_async_with(&client, |_| futures::future::ready(())),
+        );
+        check(&report);
+        assert_eq!(client.requests().len(), 10);
+    }
+
+    #[test]
+    fn transient_errors() {
+        fn status(status: StatusCode) -> ApiError<Infallible> {
+            ApiError::from_http_response(
+                ResponseError::HttpStatus {
+                    value: Value::Null,
+                    status,
+                },
+                http::Uri::from_static("/runs/a/status"),
+            )
+        }
+
+        assert!(is_transient(&status(StatusCode::INTERNAL_SERVER_ERROR)));
+        assert!(is_transient(&status(StatusCode::SERVICE_UNAVAILABLE)));
+        assert!(is_transient(&status(StatusCode::TOO_MANY_REQUESTS)));
+        assert!(!is_transient(&status(StatusCode::BAD_REQUEST)));
+        assert!(!is_transient(&status(StatusCode::FORBIDDEN)));
+        assert!(!is_transient(&status(StatusCode::NOT_FOUND)));
+        assert!(is_transient(&ApiError::<io::Error>::client(
+            io::ErrorKind::TimedOut.into()
+        )));
+        assert!(!is_transient(
+            &ApiError::<Infallible>::RequiresAuthentication
+        ));
+
+        let parse = serde_json::from_str::<Value>("<html>").unwrap_err();
+        assert!(is_transient(&ApiError::<Infallible>::from_http_response(
+            ResponseError::Parse(parse),
+            http::Uri::from_static("/runs/a/status"),
+        )));
+        let data = serde_json::from_value::<u32>(Value::Null).unwrap_err();
+        assert!(!is_transient(&ApiError::<Infallible>::from_http_response(
+            ResponseError::DataType {
+                source: data,
+                value: Value::Null,
+                typ: "u32",
+            },
+            http::Uri::from_static("/runs/a/status"),
+        )));
+    }
+
+    #[test]
+    fn csv_report() {
+        let report = BatchReport {
+            entries: vec![
+                BatchEntry {
+                    run: RunId::new("a"),
+                    status: NewStatus::Verified,
+                    attempts: 1,
+                    error: None,
+                },
+                BatchEntry {
+                    run: RunId::new("b"),
+                    status: NewStatus::Rejected {
+                        reason: "no video, \"obsolete\"".into(),
+                    },
+                    attempts: 3,
+                    error: Some("client error: timeout".into()),
+                },
+            ],
+        };
+
+        let mut csv = Vec::new();
+        report.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "run,status,reason,result,attempts,error\n\
+             a,verified,,ok,1,\n\
+             b,rejected,\"no video, \"\"obsolete\"\"\",failed,3,client error: timeout\n"
+        );
+    }
+
+    #[test]
+    fn rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(60);
+        assert_eq!(limiter.reserve(), Duration::ZERO);
+        assert!(limiter.reserve() > Duration::from_millis(900));
+    }
+}
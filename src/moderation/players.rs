@@ -0,0 +1,365 @@
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        runs::{Player, Run, RunId, UpdateRunPlayers},
+        ApiError, AsyncClient, AsyncQuery, Client, Query,
+    },
+    types,
+};
+
+/// Errors that occur when changing the players of a run.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PlayersError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The player is already a player of the run.
+    #[error("{0:?} is already a player of the run")]
+    AlreadyPresent(Player<'static>),
+    /// The player is not a player of the run.
+    #[error("{0:?} is not a player of the run")]
+    NotFound(Player<'static>),
+    /// The change would leave the run without players.
+    #[error("a run must have at least one player")]
+    Empty,
+    /// The players of the run were changed concurrently with the update.
+    #[error("the players of the run were changed concurrently")]
+    Conflict {
+        /// Players the change was applied to.
+        expected: Vec<Player<'static>>,
+        /// Players read again just before writing the change.
+        found: Vec<Player<'static>>,
+    },
+}
+
+/// Add `player` to the players of `run`.
+///
+/// Returns the updated list of players.
+pub fn add_player<C>(
+    client: &C,
+    run: &RunId<'_>,
+    player: Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: Client,
+{
+    update_players(client, run, Change::Add(player))
+}
+
+/// Remove `player` from the players of `run`.
+///
+/// Returns the updated list of players.
+pub fn remove_player<C>(
+    client: &C,
+    run: &RunId<'_>,
+    player: &Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: Client,
+{
+    update_players(client, run, Change::Remove(player))
+}
+
+/// Replace `old` with `new` in the players of `run`, keeping its position.
+///
+/// Returns the updated list of players.
+pub fn replace_player<C>(
+    client: &C,
+    run: &RunId<'_>,
+    old: &Player<'_>,
+    new: Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: Client,
+{
+    update_players(client, run, Change::Replace(old, new))
+}
+
+/// Add `player` to the players of `run` asynchronously.
+///
+/// Returns the updated list of players.
+pub async fn add_player_async<C>(
+    client: &C,
+    run: &RunId<'_>,
+    player: Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    update_players_async(client, run, Change::Add(player)).await
+}
+
+/// Remove `player` from the players of `run` asynchronously.
+///
+/// Returns the updated list of players.
+pub async fn remove_player_async<C>(
+    client: &C,
+    run: &RunId<'_>,
+    player: &Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    update_players_async(client, run, Change::Remove(player)).await
+}
+
+/// Replace `old` with `new` in the players of `run` asynchronously, keeping
+/// its position.
+///
+/// Returns the updated list of players.
+pub async fn replace_player_async<C>(
+    client: &C,
+    run: &RunId<'_>,
+    old: &Player<'_>,
+    new: Player<'_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    update_players_async(client, run, Change::Replace(old, new)).await
+}
+
+/// Read the players of `run`, apply `change` and write them back.
+///
+/// The API has no compare-and-swap for the players of a run. Instead, the run
+/// is read again just before writing and [`PlayersError::Conflict`] is
+/// returned, without writing, if its players changed since the first read. A
+/// change made between that second read and the write is still overwritten.
+fn update_players<C>(
+    client: &C,
+    run: &RunId<'_>,
+    change: Change<'_, '_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: Client,
+{
+    let endpoint = run_endpoint(run);
+    let current: types::Run<'static> = endpoint.query(client)?;
+    let mut players = players_of(&current);
+    change.apply(&mut players)?;
+    if players.is_empty() {
+        return Err(PlayersError::Empty);
+    }
+
+    let latest: types::Run<'static> = endpoint.query(client)?;
+    check_unchanged(players_of(&current), players_of(&latest))?;
+    let _: Value = update_endpoint(run, &players).query(client)?;
+    Ok(players)
+}
+
+async fn update_players_async<C>(
+    client: &C,
+    run: &RunId<'_>,
+    change: Change<'_, '_>,
+) -> Result<Vec<Player<'static>>, PlayersError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    let endpoint = run_endpoint(run);
+    let current: types::Run<'static> = endpoint.query_async(client).await?;
+    let mut players = players_of(&current);
+    change.apply(&mut players)?;
+    if players.is_empty() {
+        return Err(PlayersError::Empty);
+    }
+
+    let latest: types::Run<'static> = endpoint.query_async(client).await?;
+    check_unchanged(players_of(&current), players_of(&latest))?;
+    let _: Value = update_endpoint(run, &players).query_async(client).await?;
+    Ok(players)
+}
+
+/// A change to the players of a run.
+enum Change<'p, 'a> {
+    Add(Player<'a>),
+    Remove(&'p Player<'a>),
+    Replace(&'p Player<'a>, Player<'a>),
+}
+
+impl Change<'_, '_> {
+    fn apply<E>(self, players: &mut Vec<Player<'static>>) -> Result<(), PlayersError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        match self {
+            Change::Add(player) => add(players, player),
+            Change::Remove(player) => remove(players, player),
+            Change::Replace(old, new) => replace(players, old, new),
+        }
+    }
+}
+
+fn run_endpoint<'a>(run: &RunId<'a>) -> Run<'a> {
+    Run::builder()
+        .id(run.clone())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn update_endpoint<'a>(run: &RunId<'a>, players: &[Player<'static>]) -> UpdateRunPlayers<'a> {
+    UpdateRunPlayers::builder()
+        .id(run.clone())
+        .players(players.iter().cloned())
+        .build()
+        .expect("all required fields are set")
+}
+
+fn players_of(run: &types::Run<'static>) -> Vec<Player<'static>> {
+    run.players.iter().map(Player::from).collect()
+}
+
+fn owned(player: &Player<'_>) -> Player<'static> {
+    match player {
+        Player::User { id } => Player::User {
+            id: id.to_string().into(),
+        },
+        Player::Guest { name } => Player::Guest {
+            name: name.to_string().into(),
+        },
+    }
+}
+
+/// Check that the players read just before an update are the `expected` ones.
+fn check_unchanged<E>(
+    expected: Vec<Player<'static>>,
+    found: Vec<Player<'static>>,
+) -> Result<(), PlayersError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if expected == found {
+        Ok(())
+    } else {
+        Err(PlayersError::Conflict { expected, found })
+    }
+}
+
+fn add<E>(players: &mut Vec<Player<'static>>, player: Player<'_>) -> Result<(), PlayersError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let player = owned(&player);
+    if players.contains(&player) {
+        return Err(PlayersError::AlreadyPresent(player));
+    }
+    players.push(player);
+    Ok(())
+}
+
+fn remove<E>(players: &mut Vec<Player<'static>>, player: &Player<'_>) -> Result<(), PlayersError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let player = owned(player);
+    let len = players.len();
+    players.retain(|existing| existing != &player);
+    if players.len() == len {
+        return Err(PlayersError::NotFound(player));
+    }
+    Ok(())
+}
+
+fn replace<E>(
+    players: &mut [Player<'static>],
+    old: &Player<'_>,
+    new: Player<'_>,
+) -> Result<(), PlayersError<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    let (old, new) = (owned(old), owned(&new));
+    if old != new && players.contains(&new) {
+        return Err(PlayersError::AlreadyPresent(new));
+    }
+    let slot = players
+        .iter_mut()
+        .find(|existing| **existing == old)
+        .ok_or(PlayersError::NotFound(old))?;
+    *slot = new;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use http::Method;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{fixtures, mock::MockClient};
+
+    type Error = PlayersError<std::io::Error>;
+
+    fn user(id: &str) -> Player<'static> {
+        Player::User {
+            id: id.to_owned().into(),
+        }
+    }
+
+    #[test]
+    fn edit_players() {
+        let mut players = vec![user("a")];
+        add::<std::io::Error>(&mut players, user("b")).unwrap();
+        assert!(matches!(
+            add::<std::io::Error>(&mut players, user("b")),
+            Err(Error::AlreadyPresent(_))
+        ));
+
+        replace::<std::io::Error>(&mut players, &user("a"), user("c")).unwrap();
+        assert_eq!(players, [user("c"), user("b")]);
+
+        remove::<std::io::Error>(&mut players, &user("b")).unwrap();
+        assert!(matches!(
+            remove::<std::io::Error>(&mut players, &user("b")),
+            Err(Error::NotFound(_))
+        ));
+    }
+
+    /// A client serving run `r`, whose players are `a` when first read and
+    /// `after` from then on.
+    fn client(after: &'static str) -> MockClient {
+        let reads = AtomicUsize::new(0);
+        MockClient::new(move |request| match (&request.method, request.path()) {
+            (&Method::GET, "runs/r") => {
+                let player = match reads.fetch_add(1, Ordering::SeqCst) {
+                    0 => "a",
+                    _ => after,
+                };
+                Some(fixtures::run_json("r", player, 60.0, "2021-01-01"))
+            }
+            (&Method::PUT, "runs/r/players") => Some(json!({})),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn update_detects_conflicts() {
+        let client = self::client("a");
+        let players = replace_player(&client, &RunId::new("r"), &user("a"), user("b")).unwrap();
+        assert_eq!(players, [user("b")]);
+
+        let update = &client.requests()[2];
+        assert_eq!(update.method, Method::PUT);
+        assert_eq!(
+            update.json()["players"],
+            json!([{ "rel": "user", "id": "b" }])
+        );
+
+        let client = self::client("c");
+        assert!(matches!(
+            replace_player(&client, &RunId::new("r"), &user("a"), user("b")),
+            Err(PlayersError::Conflict { expected, found })
+                if expected == [user("a")] && found == [user("c")]
+        ));
+        let requests = client.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests.iter().all(|request| request.method == Method::GET));
+    }
+}
@@ -0,0 +1,508 @@
+//! # Render
+//!
+//! Renders a [`types::Leaderboard`] as a Markdown, HTML or fixed-width text
+//! table, e.g. for forum posts and wiki pages.
+//!
+//! Every row shows the place, the players, the time, the platform, the date
+//! and a link to the video of a run. Player and platform names are shown when
+//! the leaderboard was requested with the `Players` and `Platforms` embeds
+//! (see [`Embedded`](crate::types::Embedded)). Milliseconds are shown
+//! according to the ruleset of the game when it was embedded with `Game`.
+//! Only `http` and `https` video links are shown.
+//!
+//! The layout of each format comes from a [`Template`], which can be replaced
+//! to match the style of a community.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     api::{
+//!         leaderboards::{FullGameLeaderboard, LeaderboardEmbeds},
+//!         AsyncQuery,
+//!     },
+//!     render::{Format, Renderer, Template},
+//!     types::{Embedded, Leaderboard},
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//! let endpoint = FullGameLeaderboard::builder()
+//!     .game("o1y9wo6q")
+//!     .category("wkpoo02r")
+//!     .top(10u32)
+//!     .embeds(
+//!         [
+//!             LeaderboardEmbeds::Game,
+//!             LeaderboardEmbeds::Players,
+//!             LeaderboardEmbeds::Platforms,
+//!         ]
+//!         .into_iter(),
+//!     )
+//!     .build()?;
+//! let leaderboard: Embedded<Leaderboard<'static>> = endpoint.query_async(&client).await?;
+//!
+//! let markdown = Renderer::builder().format(Format::Markdown).build()?;
+//! println!("{}", markdown.render(&leaderboard));
+//!
+//! // A numbered list instead of a table.
+//! let list = Renderer::builder()
+//!     .format(Format::Markdown)
+//!     .template(Template {
+//!         header: String::new(),
+//!         rule: String::new(),
+//!         row: "{place}. **{players}** in {time} ({video})\n".into(),
+//!         ..Template::markdown()
+//!     })
+//!     .build()?;
+//! println!("{}", list.render(&leaderboard));
+//! # Ok(())
+//! # }
+//! ```
+use url::Url;
+
+use crate::{
+    analysis::time_for,
+    export::ExportLeaderboard,
+    text::{escape_html, substitute},
+    types::{self, Embeds},
+};
+
+/// Placeholders available in templates, with the title of their column.
+const COLUMNS: [(&str, &str); 6] = [
+    ("place", "Place"),
+    ("players", "Player"),
+    ("time", "Time"),
+    ("platform", "Platform"),
+    ("date", "Date"),
+    ("video", "Video"),
+];
+
+/// Index of the video in [`COLUMNS`].
+const VIDEO: usize = 5;
+
+/// Output format of a [`Renderer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// A Markdown table. Values are escaped.
+    Markdown,
+    /// An HTML table. Values are escaped.
+    Html,
+    /// A table for fixed-width fonts. Values are padded to the width of their
+    /// column.
+    Text,
+}
+
+/// Layout of a rendered leaderboard.
+///
+/// `header`, `rule` and `row` may contain the placeholders `{place}`,
+/// `{players}`, `{time}`, `{platform}`, `{date}` and `{video}`. In the header
+/// they are replaced by the titles of the columns, in the rule by dashes as
+/// wide as the column (for [`Format::Text`]) and in the row by the values of a
+/// run. Rows additionally support `{video_url}` and `{weblink}`, the plain
+/// links to the video and the run. Links that are not `http` or `https` URLs
+/// are left empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// Text before the table.
+    pub before: String,
+    /// The header, written once.
+    pub header: String,
+    /// Written once after the header.
+    pub rule: String,
+    /// Written for each run.
+    pub row: String,
+    /// Text after the table.
+    pub after: String,
+}
+
+/// Renders leaderboards in a [`Format`].
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct Renderer {
+    #[doc = r"Output format."]
+    format: Format,
+    #[doc = r"Layout of the output (default: the template of the format)."]
+    #[builder(default)]
+    template: Option<Template>,
+    #[doc = r"Show milliseconds, overriding the ruleset of an embedded game. If neither is known, milliseconds are shown when they are not zero."]
+    #[builder(default)]
+    show_milliseconds: Option<bool>,
+}
+
+impl Template {
+    /// The default template of `format`.
+    pub fn of(format: Format) -> Self {
+        match format {
+            Format::Markdown => Self::markdown(),
+            Format::Html => Self::html(),
+            Format::Text => Self::text(),
+        }
+    }
+
+    /// A Markdown table.
+    pub fn markdown() -> Self {
+        Self {
+            before: String::new(),
+            header: "| {place} | {players} | {time} | {platform} | {date} | {video} |\n".into(),
+            rule: "| ---: | --- | ---: | --- | --- | --- |\n".into(),
+            row: "| {place} | {players} | {time} | {platform} | {date} | {video} |\n".into(),
+            after: String::new(),
+        }
+    }
+
+    /// An HTML table.
+    pub fn html() -> Self {
+        Self {
+            before: "<table>\n".into(),
+            header: "<thead>\n<tr><th>{place}</th><th>{players}</th><th>{time}</th>\
+                     <th>{platform}</th><th>{date}</th><th>{video}</th></tr>\n</thead>\n<tbody>\n"
+                .into(),
+            rule: String::new(),
+            row: "<tr><td>{place}</td><td>{players}</td><td>{time}</td>\
+                  <td>{platform}</td><td>{date}</td><td>{video}</td></tr>\n"
+                .into(),
+            after: "</tbody>\n</table>\n".into(),
+        }
+    }
+
+    /// A table for fixed-width fonts.
+    pub fn text() -> Self {
+        let line = "{place}  {players}  {time}  {platform}  {date}  {video}\n";
+        Self {
+            before: String::new(),
+            header: line.into(),
+            rule: line.into(),
+            row: line.into(),
+            after: String::new(),
+        }
+    }
+}
+
+impl Renderer {
+    /// Create a builder for a renderer.
+    pub fn builder() -> RendererBuilder {
+        RendererBuilder::default()
+    }
+
+    /// Render `leaderboard`.
+    pub fn render<L>(&self, leaderboard: &L) -> String
+    where
+        L: ExportLeaderboard + ?Sized,
+    {
+        let embeds = leaderboard.embeds();
+        let leaderboard = leaderboard.leaderboard();
+        let show_milliseconds = self.show_milliseconds.or_else(|| {
+            let game = embeds?.game.as_ref()?;
+            Some(game.ruleset.show_milliseconds)
+        });
+        let rows: Vec<Row> = leaderboard
+            .runs
+            .iter()
+            .map(|ranked| Row::new(ranked, leaderboard, embeds, show_milliseconds))
+            .collect();
+
+        let template = self
+            .template
+            .clone()
+            .unwrap_or_else(|| Template::of(self.format));
+
+        let mut widths = [0; COLUMNS.len()];
+        if self.format == Format::Text {
+            for (column, (_, title)) in COLUMNS.iter().enumerate() {
+                widths[column] = rows
+                    .iter()
+                    .map(|row| row.values[column].chars().count())
+                    .chain([title.chars().count()])
+                    .max()
+                    .unwrap_or_default();
+            }
+        }
+
+        let mut output = template.before.clone();
+        let titles = COLUMNS.map(|(_, title)| title.to_owned());
+        output.push_str(&fill(
+            &template.header,
+            &titles.map(|title| self.escape(&title)),
+            &widths,
+            |_| None,
+        ));
+        let rule = widths.map(|width| "-".repeat(width.max(3)));
+        output.push_str(&fill(&template.rule, &rule, &[0; COLUMNS.len()], |_| None));
+        for row in &rows {
+            output.push_str(&fill(
+                &template.row,
+                &self.cells(row),
+                &widths,
+                |name| match name {
+                    "video_url" => Some(self.escape_url(&row.video_url)),
+                    "weblink" => Some(self.escape_url(&row.weblink)),
+                    _ => None,
+                },
+            ));
+        }
+        output.push_str(&template.after);
+
+        if self.format == Format::Text {
+            let mut trimmed: String = output
+                .lines()
+                .map(|line| line.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if output.ends_with('\n') {
+                trimmed.push('\n');
+            }
+            return trimmed;
+        }
+        output
+    }
+
+    /// The values of `row` as they appear in the output.
+    fn cells(&self, row: &Row) -> [String; COLUMNS.len()] {
+        let mut cells = row.values.clone().map(|value| self.escape(&value));
+        cells[VIDEO] = match self.format {
+            _ if row.video_url.is_empty() => String::new(),
+            Format::Markdown => format!("[Video]({})", self.escape_url(&row.video_url)),
+            Format::Html => format!("<a href=\"{}\">Video</a>", self.escape_url(&row.video_url)),
+            Format::Text => row.video_url.clone(),
+        };
+        cells
+    }
+
+    fn escape(&self, value: &str) -> String {
+        match self.format {
+            Format::Markdown => escape_markdown(value),
+            Format::Html => escape_html(value),
+            Format::Text => value.to_owned(),
+        }
+    }
+
+    fn escape_url(&self, url: &str) -> String {
+        match self.format {
+            Format::Markdown => url
+                .replace(' ', "%20")
+                .replace('(', "%28")
+                .replace(')', "%29")
+                .replace('|', "%7C"),
+            Format::Html => escape_html(url),
+            Format::Text => url.to_owned(),
+        }
+    }
+}
+
+/// The values of a run, in the order of [`COLUMNS`].
+struct Row {
+    values: [String; COLUMNS.len()],
+    video_url: String,
+    weblink: String,
+}
+
+impl Row {
+    fn new(
+        ranked: &types::RankedRun<'_>,
+        leaderboard: &types::Leaderboard<'_>,
+        embeds: Option<&Embeds<'_>>,
+        show_milliseconds: Option<bool>,
+    ) -> Self {
+        let run = &ranked.run;
+        let players: Vec<String> = run
+            .players
+            .iter()
+            .map(|player| match (embeds, player) {
+                (Some(embeds), _) => embeds.player_name(player),
+                (None, types::Player::User { id, .. }) => id.to_string(),
+                (None, types::Player::Guest { name, .. }) => name.clone(),
+            })
+            .collect();
+        let time = time_for(run, leaderboard.timing.as_ref()).unwrap_or(run.times.primary_t);
+        let platform = run.system.platform.as_ref().map(|platform| {
+            embeds
+                .and_then(|embeds| embeds.platform(platform))
+                .map_or_else(|| platform.to_string(), |platform| platform.name.clone())
+        });
+        let video_url = run
+            .videos
+            .as_ref()
+            .and_then(|videos| videos.links.first())
+            .filter(|link| is_web_url(&link.uri))
+            .map(|link| link.uri.clone())
+            .unwrap_or_default();
+
+        Self {
+            values: [
+                ranked.place.to_string(),
+                players.join(", "),
+                format_time(time, show_milliseconds),
+                platform.unwrap_or_default(),
+                run.date.clone().unwrap_or_default(),
+                video_url.clone(),
+            ],
+            video_url,
+            weblink: Some(&run.weblink)
+                .filter(|weblink| is_web_url(weblink))
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Replace the placeholders of `template` by `values`, padded to `widths`,
+/// and the other placeholders by `extra`. Places and times are aligned to the
+/// right.
+fn fill<F>(template: &str, values: &[String; COLUMNS.len()], widths: &[usize], extra: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    substitute(template, |placeholder| {
+        let Some(column) = COLUMNS.iter().position(|(name, _)| *name == placeholder) else {
+            return extra(placeholder);
+        };
+        let (width, value) = (widths[column], &values[column]);
+        Some(if matches!(placeholder, "place" | "time") {
+            format!("{value:>width$}")
+        } else {
+            format!("{value:<width$}")
+        })
+    })
+}
+
+/// Whether `url` is an `http` or `https` URL. Other links are not rendered.
+fn is_web_url(url: &str) -> bool {
+    Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+fn escape_markdown(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(
+            c,
+            '\\' | '|' | '*' | '_' | '`' | '[' | ']' | '<' | '>' | '{' | '}'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Format `seconds` as `[h:]mm:ss[.mmm]`. Without `show_milliseconds`,
+/// milliseconds are shown when they are not zero.
+pub(crate) fn format_time(seconds: f64, show_milliseconds: Option<bool>) -> String {
+    let millis = (seconds * 1000.0).round() as u64;
+    let (hours, minutes, secs, millis) = (
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+    );
+    let mut time = if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    };
+    if show_milliseconds.unwrap_or(millis > 0) {
+        time.push_str(&format!(".{millis:03}"));
+    }
+    time
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn time_format() {
+        assert_eq!(format_time(4263.0, None), "1:11:03");
+        assert_eq!(format_time(62.25, None), "1:02.250");
+        assert_eq!(format_time(62.25, Some(false)), "1:02");
+        assert_eq!(format_time(62.0, Some(true)), "1:02.000");
+    }
+
+    /// A leaderboard with one run of the guest `name`, with a video at `video`.
+    fn leaderboard(name: &str, video: &str) -> types::Embedded<types::Leaderboard<'static>> {
+        serde_json::from_value(json!({
+            "weblink": "",
+            "game": "g",
+            "category": "c",
+            "video-only": false,
+            "runs": [{
+                "place": 1,
+                "run": {
+                    "id": "r1",
+                    "weblink": "https://www.speedrun.com/g/run/r1",
+                    "game": "g",
+                    "category": "c",
+                    "videos": { "links": [{ "uri": video }] },
+                    "status": { "status": "verified" },
+                    "players": [{ "rel": "guest", "name": name, "uri": "" }],
+                    "date": "2021-01-01",
+                    "times": {
+                        "primary": "PT62.5S", "primary_t": 62.5,
+                        "realtime": "PT62.5S", "realtime_t": 62.5,
+                        "realtime_noloads": null, "realtime_noloads_t": 0.0,
+                        "ingame": null, "ingame_t": 0.0
+                    },
+                    "system": { "platform": "p", "emulated": false, "region": null }
+                }
+            }],
+            "links": [],
+            "platforms": { "data": [
+                { "id": "p", "name": "PC", "released": 1981, "links": [] }
+            ] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn render_text_and_markdown() {
+        let leaderboard = leaderboard("a_b", "https://youtu.be/x");
+        let text = Renderer::builder()
+            .format(Format::Text)
+            .show_milliseconds(false)
+            .build()
+            .unwrap();
+        assert_eq!(
+            text.render(&leaderboard),
+            "Place  Player  Time  Platform  Date        Video\n\
+             -----  ------  ----  --------  ----------  ------------------\n    \
+             1  a_b     1:02  PC        2021-01-01  https://youtu.be/x\n"
+        );
+
+        let markdown = Renderer::builder()
+            .format(Format::Markdown)
+            .build()
+            .unwrap();
+        assert_eq!(
+            markdown.render(&leaderboard).lines().nth(2),
+            Some("| 1 | a\\_b | 1:02.500 | PC | 2021-01-01 | [Video](https://youtu.be/x) |")
+        );
+    }
+
+    #[test]
+    fn render_placeholders_and_links_safely() {
+        let leaderboard = leaderboard("{weblink}", "javascript:alert(1)");
+        let template = Template {
+            row: "{players} {video_url} {weblink}\n".into(),
+            ..Template::html()
+        };
+        let html = Renderer::builder()
+            .format(Format::Html)
+            .template(template)
+            .build()
+            .unwrap();
+        let output = html.render(&leaderboard);
+        assert!(output.contains("\n{weblink}  https://www.speedrun.com/g/run/r1\n"));
+        assert!(!output.contains("javascript"));
+
+        let markdown = Renderer::builder()
+            .format(Format::Markdown)
+            .build()
+            .unwrap();
+        assert_eq!(
+            markdown.render(&leaderboard).lines().nth(2),
+            Some("| 1 | \\{weblink\\} | 1:02.500 | PC | 2021-01-01 |  |")
+        );
+    }
+}
@@ -0,0 +1,730 @@
+//! # Resolver
+//!
+//! Resolves human-friendly names (game abbreviations, category/level/variable
+//! names and value labels) into the IDs used by the endpoints.
+//!
+//! Results are cached by the [`Resolver`], so resolving several names for the
+//! same game only requests the game's categories, levels and variables once.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{resolver::Resolver, SpeedrunApiBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//! let mut resolver = Resolver::new(&client);
+//!
+//! let game = resolver.game_async("sms").await?;
+//! let category = resolver.category_async(&game, "Any%").await?;
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::HashMap, error::Error, fmt::Display};
+
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        games::{GameCategories, GameId, GameLevels, GameVariables, Games},
+        levels::LevelId,
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, Query,
+    },
+    types::{self, CategoryType},
+};
+
+/// Maximum number of close matches reported in errors.
+const MAX_CANDIDATES: usize = 5;
+
+/// The kind of resource being resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResolveKind {
+    /// A game, resolved by abbreviation.
+    Game,
+    /// A category, resolved by name.
+    Category,
+    /// A level, resolved by name.
+    Level,
+    /// A variable, resolved by name.
+    Variable,
+    /// A variable value, resolved by label.
+    Value,
+}
+
+/// Errors that occur when resolving names.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ResolveError<E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    /// Error querying the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// No resource matched the name.
+    #[error("no {kind} named `{name}`{}", did_you_mean(.candidates))]
+    NotFound {
+        /// Kind of resource that was resolved.
+        kind: ResolveKind,
+        /// Name that was resolved.
+        name: String,
+        /// Close matches for the name.
+        candidates: Vec<String>,
+    },
+    /// More than one resource matched the name.
+    #[error("{kind} `{name}` is ambiguous, candidates: {}", .candidates.join(", "))]
+    Ambiguous {
+        /// Kind of resource that was resolved.
+        kind: ResolveKind,
+        /// Name that was resolved.
+        name: String,
+        /// All resources matching the name.
+        candidates: Vec<String>,
+    },
+}
+
+/// Resolves names into IDs, caching results.
+///
+/// Methods ending in `_async` use an [`AsyncClient`], the others a [`Client`].
+#[derive(Debug)]
+pub struct Resolver<'c, C> {
+    client: &'c C,
+    games: HashMap<String, GameId<'static>>,
+    game_names: HashMap<String, GameId<'static>>,
+    categories: HashMap<GameId<'static>, Vec<types::Category<'static>>>,
+    levels: HashMap<GameId<'static>, Vec<types::Level<'static>>>,
+    variables: HashMap<GameId<'static>, Vec<types::Variable<'static>>>,
+}
+
+impl<'c, C> Resolver<'c, C> {
+    /// Create a new resolver using `client`.
+    pub fn new(client: &'c C) -> Self {
+        Self {
+            client,
+            games: HashMap::new(),
+            game_names: HashMap::new(),
+            categories: HashMap::new(),
+            levels: HashMap::new(),
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Clear all cached results.
+    pub fn clear(&mut self) {
+        self.games.clear();
+        self.game_names.clear();
+        self.categories.clear();
+        self.levels.clear();
+        self.variables.clear();
+    }
+}
+
+impl<C> Resolver<'_, C>
+where
+    C: Client,
+{
+    /// Resolve a game abbreviation into a [`GameId`].
+    pub fn game(&mut self, abbreviation: &str) -> Result<GameId<'static>, ResolveError<C::Error>> {
+        let key = normalize(abbreviation);
+        if let Some(id) = self.games.get(&key) {
+            return Ok(id.clone());
+        }
+
+        let endpoint = Games::builder()
+            .abbreviation(abbreviation)
+            .build()
+            .expect("all required fields are set");
+        let games: Vec<types::Game<'static>> = endpoint.query(self.client)?;
+        if games.is_empty() {
+            let endpoint = Games::builder()
+                .name(abbreviation)
+                .build()
+                .expect("all required fields are set");
+            let similar = endpoint.query(self.client)?;
+            return Err(game_not_found(abbreviation, similar));
+        }
+
+        let id = select_game(abbreviation, games)?;
+        self.games.insert(key, id.clone());
+        Ok(id)
+    }
+
+    /// Resolve the full name of a game into a [`GameId`].
+    pub fn game_by_name(&mut self, name: &str) -> Result<GameId<'static>, ResolveError<C::Error>> {
+        let key = normalize(name);
+        if let Some(id) = self.game_names.get(&key) {
+            return Ok(id.clone());
+        }
+
+        let endpoint = Games::builder()
+            .name(name)
+            .build()
+            .expect("all required fields are set");
+        let games: Vec<types::Game<'static>> = endpoint.query(self.client)?;
+        let id = select_game_by_name(name, &games)?;
+        self.game_names.insert(key, id.clone());
+        Ok(id)
+    }
+
+    /// Resolve a category name of `game` into a [`CategoryId`].
+    pub fn category(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+    ) -> Result<CategoryId<'static>, ResolveError<C::Error>> {
+        let categories = self.fetch_categories(game)?;
+        select_category(categories, name, None)
+    }
+
+    /// Resolve a category name of `game` into a [`CategoryId`], only
+    /// considering categories of type `kind`.
+    pub fn category_of_type(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+        kind: CategoryType,
+    ) -> Result<CategoryId<'static>, ResolveError<C::Error>> {
+        let categories = self.fetch_categories(game)?;
+        select_category(categories, name, Some(kind))
+    }
+
+    /// Resolve a level name of `game` into a [`LevelId`].
+    pub fn level(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+    ) -> Result<LevelId<'static>, ResolveError<C::Error>> {
+        let levels = self.fetch_levels(game)?;
+        select_level(levels, name)
+    }
+
+    /// Resolve a variable name of `game` into a [`VariableId`].
+    ///
+    /// When `category` is given, only variables applicable to that category are
+    /// considered.
+    pub fn variable(
+        &mut self,
+        game: &GameId<'_>,
+        category: Option<&CategoryId<'_>>,
+        name: &str,
+    ) -> Result<VariableId<'static>, ResolveError<C::Error>> {
+        let variables = self.fetch_variables(game)?;
+        select_variable(variables, category, name).map(|variable| variable.id.clone())
+    }
+
+    /// Resolve a value label of a variable of `game` into a [`ValueId`].
+    pub fn value(
+        &mut self,
+        game: &GameId<'_>,
+        variable: &VariableId<'_>,
+        label: &str,
+    ) -> Result<ValueId<'static>, ResolveError<C::Error>> {
+        let variables = self.fetch_variables(game)?;
+        select_value(variables, variable, label)
+    }
+
+    fn fetch_categories(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Category<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.categories.contains_key(&key) {
+            let endpoint = GameCategories::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let categories = endpoint.query(self.client)?;
+            self.categories.insert(key.clone(), categories);
+        }
+        Ok(&self.categories[&key])
+    }
+
+    fn fetch_levels(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Level<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.levels.contains_key(&key) {
+            let endpoint = GameLevels::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let levels = endpoint.query(self.client)?;
+            self.levels.insert(key.clone(), levels);
+        }
+        Ok(&self.levels[&key])
+    }
+
+    fn fetch_variables(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Variable<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.variables.contains_key(&key) {
+            let endpoint = GameVariables::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let variables = endpoint.query(self.client)?;
+            self.variables.insert(key.clone(), variables);
+        }
+        Ok(&self.variables[&key])
+    }
+}
+
+impl<C> Resolver<'_, C>
+where
+    C: AsyncClient + Sync,
+{
+    /// Resolve a game abbreviation into a [`GameId`].
+    pub async fn game_async(
+        &mut self,
+        abbreviation: &str,
+    ) -> Result<GameId<'static>, ResolveError<C::Error>> {
+        let key = normalize(abbreviation);
+        if let Some(id) = self.games.get(&key) {
+            return Ok(id.clone());
+        }
+
+        let endpoint = Games::builder()
+            .abbreviation(abbreviation)
+            .build()
+            .expect("all required fields are set");
+        let games: Vec<types::Game<'static>> = endpoint.query_async(self.client).await?;
+        if games.is_empty() {
+            let endpoint = Games::builder()
+                .name(abbreviation)
+                .build()
+                .expect("all required fields are set");
+            let similar = endpoint.query_async(self.client).await?;
+            return Err(game_not_found(abbreviation, similar));
+        }
+
+        let id = select_game(abbreviation, games)?;
+        self.games.insert(key, id.clone());
+        Ok(id)
+    }
+
+    /// Resolve the full name of a game into a [`GameId`].
+    pub async fn game_by_name_async(
+        &mut self,
+        name: &str,
+    ) -> Result<GameId<'static>, ResolveError<C::Error>> {
+        let key = normalize(name);
+        if let Some(id) = self.game_names.get(&key) {
+            return Ok(id.clone());
+        }
+
+        let endpoint = Games::builder()
+            .name(name)
+            .build()
+            .expect("all required fields are set");
+        let games: Vec<types::Game<'static>> = endpoint.query_async(self.client).await?;
+        let id = select_game_by_name(name, &games)?;
+        self.game_names.insert(key, id.clone());
+        Ok(id)
+    }
+
+    /// Resolve a category name of `game` into a [`CategoryId`].
+    pub async fn category_async(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+    ) -> Result<CategoryId<'static>, ResolveError<C::Error>> {
+        let categories = self.fetch_categories_async(game).await?;
+        select_category(categories, name, None)
+    }
+
+    /// Resolve a category name of `game` into a [`CategoryId`], only
+    /// considering categories of type `kind`.
+    pub async fn category_of_type_async(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+        kind: CategoryType,
+    ) -> Result<CategoryId<'static>, ResolveError<C::Error>> {
+        let categories = self.fetch_categories_async(game).await?;
+        select_category(categories, name, Some(kind))
+    }
+
+    /// Resolve a level name of `game` into a [`LevelId`].
+    pub async fn level_async(
+        &mut self,
+        game: &GameId<'_>,
+        name: &str,
+    ) -> Result<LevelId<'static>, ResolveError<C::Error>> {
+        let levels = self.fetch_levels_async(game).await?;
+        select_level(levels, name)
+    }
+
+    /// Resolve a variable name of `game` into a [`VariableId`].
+    ///
+    /// When `category` is given, only variables applicable to that category are
+    /// considered.
+    pub async fn variable_async(
+        &mut self,
+        game: &GameId<'_>,
+        category: Option<&CategoryId<'_>>,
+        name: &str,
+    ) -> Result<VariableId<'static>, ResolveError<C::Error>> {
+        let variables = self.fetch_variables_async(game).await?;
+        select_variable(variables, category, name).map(|variable| variable.id.clone())
+    }
+
+    /// Resolve a value label of a variable of `game` into a [`ValueId`].
+    pub async fn value_async(
+        &mut self,
+        game: &GameId<'_>,
+        variable: &VariableId<'_>,
+        label: &str,
+    ) -> Result<ValueId<'static>, ResolveError<C::Error>> {
+        let variables = self.fetch_variables_async(game).await?;
+        select_value(variables, variable, label)
+    }
+
+    async fn fetch_categories_async(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Category<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.categories.contains_key(&key) {
+            let endpoint = GameCategories::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let categories = endpoint.query_async(self.client).await?;
+            self.categories.insert(key.clone(), categories);
+        }
+        Ok(&self.categories[&key])
+    }
+
+    async fn fetch_levels_async(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Level<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.levels.contains_key(&key) {
+            let endpoint = GameLevels::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let levels = endpoint.query_async(self.client).await?;
+            self.levels.insert(key.clone(), levels);
+        }
+        Ok(&self.levels[&key])
+    }
+
+    async fn fetch_variables_async(
+        &mut self,
+        game: &GameId<'_>,
+    ) -> Result<&[types::Variable<'static>], ResolveError<C::Error>> {
+        let key = owned_game_id(game);
+        if !self.variables.contains_key(&key) {
+            let endpoint = GameVariables::builder()
+                .id(key.clone())
+                .build()
+                .expect("all required fields are set");
+            let variables = endpoint.query_async(self.client).await?;
+            self.variables.insert(key.clone(), variables);
+        }
+        Ok(&self.variables[&key])
+    }
+}
+
+impl Display for ResolveKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            ResolveKind::Game => "game",
+            ResolveKind::Category => "category",
+            ResolveKind::Level => "level",
+            ResolveKind::Variable => "variable",
+            ResolveKind::Value => "value",
+        };
+        write!(f, "{kind}")
+    }
+}
+
+fn owned_game_id(game: &GameId<'_>) -> GameId<'static> {
+    GameId::new(game.to_string())
+}
+
+fn game_not_found<E>(abbreviation: &str, similar: Vec<types::Game<'_>>) -> ResolveError<E>
+where
+    E: Error + Send + Sync + 'static,
+{
+    ResolveError::NotFound {
+        kind: ResolveKind::Game,
+        name: abbreviation.to_owned(),
+        candidates: similar
+            .iter()
+            .take(MAX_CANDIDATES)
+            .map(|game| format!("{} ({})", game.abbreviation, game.names.international))
+            .collect(),
+    }
+}
+
+fn select_game<E>(
+    abbreviation: &str,
+    games: Vec<types::Game<'static>>,
+) -> Result<GameId<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    match <[_; 1]>::try_from(games) {
+        Ok([game]) => Ok(game.id),
+        Err(games) => Err(ResolveError::Ambiguous {
+            kind: ResolveKind::Game,
+            name: abbreviation.to_owned(),
+            candidates: games
+                .iter()
+                .map(|game| format!("{} ({})", game.names.international, game.id))
+                .collect(),
+        }),
+    }
+}
+
+fn select_game_by_name<E>(
+    name: &str,
+    games: &[types::Game<'static>],
+) -> Result<GameId<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    select(
+        ResolveKind::Game,
+        name,
+        games,
+        |game| &game.names.international,
+        |game| format!("{} ({})", game.names.international, game.id),
+    )
+    .map(|game| game.id.clone())
+}
+
+fn select_category<E>(
+    categories: &[types::Category<'static>],
+    name: &str,
+    kind: Option<CategoryType>,
+) -> Result<CategoryId<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    let categories: Vec<_> = categories
+        .iter()
+        .filter(|category| {
+            kind.as_ref()
+                .is_none_or(|kind| &category.type_field == kind)
+        })
+        .collect();
+    let describe = |category: &types::Category<'_>| match category.type_field {
+        CategoryType::PerGame => format!("{} (full-game)", category.name),
+        CategoryType::PerLevel => format!("{} (per-level)", category.name),
+    };
+    select(
+        ResolveKind::Category,
+        name,
+        &categories,
+        |category| &category.name,
+        |category| describe(category),
+    )
+    .map(|category| category.id.clone())
+}
+
+fn select_level<E>(
+    levels: &[types::Level<'static>],
+    name: &str,
+) -> Result<LevelId<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    let levels: Vec<_> = levels.iter().collect();
+    select(
+        ResolveKind::Level,
+        name,
+        &levels,
+        |level| &level.name,
+        |level| format!("{} ({})", level.name, level.id),
+    )
+    .map(|level| level.id.clone())
+}
+
+fn select_variable<'v, E>(
+    variables: &'v [types::Variable<'static>],
+    category: Option<&CategoryId<'_>>,
+    name: &str,
+) -> Result<&'v types::Variable<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    let variables: Vec<_> = variables
+        .iter()
+        .filter(|variable| match (category, &variable.category) {
+            (Some(category), Some(applies_to)) => category == applies_to,
+            _ => true,
+        })
+        .collect();
+    select(
+        ResolveKind::Variable,
+        name,
+        &variables,
+        |variable| &variable.name,
+        |variable| format!("{} ({})", variable.name, variable.id),
+    )
+    .copied()
+}
+
+fn select_value<E>(
+    variables: &[types::Variable<'static>],
+    variable: &VariableId<'_>,
+    label: &str,
+) -> Result<ValueId<'static>, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    let values: Vec<_> = variables
+        .iter()
+        .find(|var| &var.id == variable)
+        .map(|var| var.values.values.iter().collect())
+        .unwrap_or_default();
+    select(
+        ResolveKind::Value,
+        label,
+        &values,
+        |(_, value)| &value.label,
+        |(_, value)| value.label.clone(),
+    )
+    .map(|(id, _)| (*id).clone())
+}
+
+/// Select the single item whose name matches `name` (case-insensitive).
+fn select<'i, T, E>(
+    kind: ResolveKind,
+    name: &str,
+    items: &'i [T],
+    item_name: impl Fn(&T) -> &str,
+    describe: impl Fn(&T) -> String,
+) -> Result<&'i T, ResolveError<E>>
+where
+    E: Error + Send + Sync + 'static,
+{
+    let wanted = normalize(name);
+    let matches: Vec<_> = items
+        .iter()
+        .filter(|item| normalize(item_name(item)) == wanted)
+        .collect();
+
+    match matches.as_slice() {
+        [item] => Ok(item),
+        [] => Err(ResolveError::NotFound {
+            kind,
+            name: name.to_owned(),
+            candidates: close_matches(&wanted, items.iter().map(item_name)),
+        }),
+        _ => Err(ResolveError::Ambiguous {
+            kind,
+            name: name.to_owned(),
+            candidates: matches.into_iter().map(describe).collect(),
+        }),
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Names similar to `wanted`, best matches first.
+fn close_matches<'n>(wanted: &str, names: impl Iterator<Item = &'n str>) -> Vec<String> {
+    let threshold = (wanted.chars().count() / 3).max(2);
+    let mut scored: Vec<_> = names
+        .filter_map(|name| {
+            let normalized = normalize(name);
+            let distance = if normalized.contains(wanted) || wanted.contains(&normalized) {
+                0
+            } else {
+                edit_distance(wanted, &normalized)
+            };
+            (distance <= threshold).then(|| (distance, name.to_owned()))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(MAX_CANDIDATES)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                previous.min(row[j]).min(current) + 1
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+fn did_you_mean(candidates: &[String]) -> String {
+    if candidates.is_empty() {
+        String::new()
+    } else {
+        format!(", did you mean: {}?", candidates.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn suggests_close_matches() {
+        let names = [
+            "Any%",
+            "Any% No Major Glitches",
+            "120 Shines",
+            "All Episodes",
+        ];
+        assert_eq!(
+            close_matches("any", names.into_iter()),
+            vec!["Any%", "Any% No Major Glitches"]
+        );
+        assert_eq!(
+            close_matches("120 shine", names.into_iter()),
+            vec!["120 Shines"]
+        );
+        assert!(close_matches("low%", names.into_iter()).is_empty());
+    }
+
+    #[test]
+    fn selects_case_insensitive() {
+        let names = ["Any%", "any%", "120 Shines"];
+        let result = select::<_, std::io::Error>(
+            ResolveKind::Category,
+            "120 SHINES",
+            &names,
+            |name| name,
+            |name| name.to_string(),
+        );
+        assert_eq!(result.unwrap(), &"120 Shines");
+
+        let result = select::<_, std::io::Error>(
+            ResolveKind::Category,
+            "ANY%",
+            &names,
+            |name| name,
+            |name| name.to_string(),
+        );
+        assert!(matches!(result, Err(ResolveError::Ambiguous { .. })));
+    }
+}
@@ -0,0 +1,535 @@
+//! # Splits.io
+//!
+//! A small client for the [splits.io](https://splits.io) API, used to retrieve
+//! the splits attached to runs.
+//!
+//! The clients implement the same [`Client`] and [`AsyncClient`] traits as
+//! the speedrun.com clients, with a configurable base URL.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     api::{runs::Run, AsyncQuery},
+//!     splitsio::{self, SplitsIoBuilder},
+//!     types, SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//! let splits_client = SplitsIoBuilder::new().build_async()?;
+//!
+//! let run: types::Run<'_> = Run::builder().id("90y6pm7e").build()?.query_async(&client).await?;
+//! if let Some(splits) = splitsio::run_splits_async(&splits_client, &run).await? {
+//!     for segment in splits.segments {
+//!         println!("{}: {:?}", segment.name, segment.realtime_duration_ms);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use futures::TryFutureExt;
+use http::{request::Builder as RequestBuilder, Method};
+use log::debug;
+use reqwest::{blocking::Client as HttpClient, Client as AsyncHttpClient};
+use serde::{de::DeserializeOwned, Deserialize};
+use url::Url;
+
+use crate::{
+    api::{
+        self, query::url_to_http_uri, utils::deserialize_json, ApiError, AsyncClient, AsyncQuery,
+        Client, Query, RestClient,
+    },
+    client::{execute, execute_async},
+    error::{RestError, SpeedrunApiResult},
+    types,
+};
+
+const SPLITSIO_API_BASE_URL: &str = "https://splits.io/api/v4/";
+
+/// A client for communicating with the splits.io API
+#[derive(Clone, Debug)]
+pub struct SplitsIoClient {
+    client: HttpClient,
+    rest_url: Url,
+}
+
+impl SplitsIoClient {
+    /// Create a new splits.io API client.
+    pub fn new() -> SpeedrunApiResult<Self> {
+        SplitsIoBuilder::new().build()
+    }
+
+    /// Create a new splits.io API builder.
+    pub fn builder() -> SplitsIoBuilder {
+        SplitsIoBuilder::new()
+    }
+}
+
+impl RestClient for SplitsIoClient {
+    type Error = RestError;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        debug!("splits.io api call {}", endpoint);
+        self.rest_url
+            .join(endpoint.trim_start_matches('/'))
+            .map_err(From::from)
+    }
+
+    fn has_api_key(&self) -> bool {
+        false
+    }
+}
+
+impl Client for SplitsIoClient {
+    fn rest(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<http::Response<bytes::Bytes>, ApiError<Self::Error>> {
+        let request = request
+            .body(body)
+            .map_err(|err| ApiError::client(err.into()))?;
+        execute(&self.client, request).map_err(ApiError::client)
+    }
+}
+
+/// An asynchronous client for communicating with the splits.io API
+#[derive(Clone, Debug)]
+pub struct SplitsIoClientAsync {
+    client: AsyncHttpClient,
+    rest_url: Url,
+}
+
+impl SplitsIoClientAsync {
+    /// Create a new asynchronous splits.io API client.
+    pub fn new() -> SpeedrunApiResult<Self> {
+        SplitsIoBuilder::new().build_async()
+    }
+
+    /// Create a new splits.io API builder.
+    pub fn builder() -> SplitsIoBuilder {
+        SplitsIoBuilder::new()
+    }
+}
+
+impl RestClient for SplitsIoClientAsync {
+    type Error = RestError;
+
+    fn rest_endpoint(&self, endpoint: &str) -> Result<Url, ApiError<Self::Error>> {
+        debug!("splits.io api call {}", endpoint);
+        self.rest_url
+            .join(endpoint.trim_start_matches('/'))
+            .map_err(From::from)
+    }
+
+    fn has_api_key(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl AsyncClient for SplitsIoClientAsync {
+    async fn rest_async(
+        &self,
+        request: RequestBuilder,
+        body: Vec<u8>,
+    ) -> Result<http::Response<bytes::Bytes>, ApiError<Self::Error>> {
+        let request = request
+            .body(body)
+            .map_err(|err| ApiError::client(err.into()))?;
+        execute_async(&self.client, request)
+            .map_err(ApiError::client)
+            .await
+    }
+}
+
+/// splits.io API client builder
+#[derive(Debug, Default)]
+pub struct SplitsIoBuilder {
+    base_url: Option<String>,
+}
+
+impl SplitsIoBuilder {
+    /// Create a new splits.io API client builder.
+    pub fn new() -> Self {
+        SplitsIoBuilder::default()
+    }
+
+    /// Use a different base URL for the API (default:
+    /// `https://splits.io/api/v4/`).
+    pub fn base_url<S>(&mut self, value: S) -> &mut Self
+    where
+        S: Into<String>,
+    {
+        self.base_url = Some(value.into());
+        self
+    }
+
+    /// Build a synchronous splits.io API client.
+    pub fn build(&self) -> SpeedrunApiResult<SplitsIoClient> {
+        Ok(SplitsIoClient {
+            client: HttpClient::new(),
+            rest_url: self.rest_url()?,
+        })
+    }
+
+    /// Build an asynchronous splits.io API client.
+    pub fn build_async(&self) -> SpeedrunApiResult<SplitsIoClientAsync> {
+        Ok(SplitsIoClientAsync {
+            client: AsyncHttpClient::new(),
+            rest_url: self.rest_url()?,
+        })
+    }
+
+    fn rest_url(&self) -> Result<Url, url::ParseError> {
+        let base_url = self.base_url.as_deref().unwrap_or(SPLITSIO_API_BASE_URL);
+        // Relative endpoints are joined onto the base URL, which must end with a
+        // slash to keep its last path segment.
+        if base_url.ends_with('/') {
+            Url::parse(base_url)
+        } else {
+            Url::parse(&format!("{base_url}/"))
+        }
+    }
+}
+
+/// Retrieves a run from splits.io, including its segments.
+///
+/// Can be queried into [`SplitsRun`], or any other type matching the `run`
+/// object of the splits.io API.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct RunSplits<'a> {
+    #[doc = r"splits.io `ID` of the run."]
+    id: Cow<'a, str>,
+    #[doc = r"Include the attempt history and the history of each segment (default: false)."]
+    #[builder(default)]
+    historic: bool,
+}
+
+impl<'a> RunSplits<'a> {
+    /// Create a builder for this endpoint.
+    pub fn builder() -> RunSplitsBuilder<'a> {
+        RunSplitsBuilder::default()
+    }
+
+    /// Endpoint for the splits of a speedrun.com run, including their history.
+    ///
+    /// Returns `None` if no splits are attached to the run.
+    pub fn for_run(run: &'a types::Run<'_>) -> Option<Self> {
+        let id = splits_id(run.splits.as_ref()?)?;
+        Some(Self {
+            id: Cow::Borrowed(id),
+            historic: true,
+        })
+    }
+
+    fn request<C>(&self, client: &C) -> Result<RequestBuilder, ApiError<C::Error>>
+    where
+        C: RestClient,
+    {
+        let mut url = client.rest_endpoint(&format!("runs/{}", self.id))?;
+        if self.historic {
+            url.query_pairs_mut().append_pair("historic", "1");
+        }
+        Ok(RequestBuilder::new()
+            .method(Method::GET)
+            .uri(url_to_http_uri(url)))
+    }
+}
+
+#[derive(Deserialize)]
+struct RunRoot<T> {
+    run: T,
+}
+
+impl<T, C> Query<T, C> for RunSplits<'_>
+where
+    T: DeserializeOwned,
+    C: Client,
+{
+    fn query(&self, client: &C) -> Result<T, ApiError<C::Error>> {
+        let req = self.request(client)?;
+        let url = req.uri_ref().cloned().unwrap_or_default();
+
+        let rsp = client.rest(req, Vec::new())?;
+
+        deserialize_json::<RunRoot<T>>(rsp, std::any::type_name::<T>())
+            .map(|root| root.run)
+            .map_err(|err| ApiError::from_http_response(err, url))
+    }
+}
+
+#[async_trait]
+impl<T, C> AsyncQuery<T, C> for RunSplits<'_>
+where
+    T: DeserializeOwned + 'static,
+    C: AsyncClient + Sync,
+{
+    async fn query_async(&self, client: &C) -> Result<T, ApiError<C::Error>> {
+        let req = self.request(client)?;
+        let url = req.uri_ref().cloned().unwrap_or_default();
+
+        let rsp = client.rest_async(req, Vec::new()).await?;
+
+        deserialize_json::<RunRoot<T>>(rsp, std::any::type_name::<T>())
+            .map(|root| root.run)
+            .map_err(|err| ApiError::from_http_response(err, url))
+    }
+}
+
+/// A run on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsRun {
+    /// splits.io `ID` of the run.
+    pub id: String,
+    /// speedrun.com `ID` of the run, if linked.
+    pub srdc_id: Option<String>,
+    /// Final real time of the run in milliseconds.
+    pub realtime_duration_ms: Option<i64>,
+    /// Sum of the best real time segments in milliseconds.
+    pub realtime_sum_of_best_ms: Option<i64>,
+    /// Final game time of the run in milliseconds.
+    pub gametime_duration_ms: Option<i64>,
+    /// Sum of the best game time segments in milliseconds.
+    pub gametime_sum_of_best_ms: Option<i64>,
+    /// Timing method used by the runner (`real` or `game`).
+    pub default_timing: Option<String>,
+    /// Timer program the splits were uploaded from.
+    pub program: Option<String>,
+    /// Number of attempts.
+    pub attempts: Option<i64>,
+    /// Video of the run.
+    pub video_url: Option<String>,
+    /// Game of the run.
+    pub game: Option<SplitsGame>,
+    /// Category of the run.
+    pub category: Option<SplitsCategory>,
+    /// Runners of the run.
+    #[serde(default)]
+    pub runners: Vec<SplitsRunner>,
+    /// Segments of the run.
+    #[serde(default)]
+    pub segments: Vec<SplitsSegment>,
+    /// Attempt history, only included when requested.
+    #[serde(default)]
+    pub histories: Vec<SplitsAttempt>,
+}
+
+/// A game on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsGame {
+    /// splits.io `ID` of the game.
+    pub id: String,
+    /// Name of the game.
+    pub name: String,
+    /// Short name of the game.
+    pub shortname: Option<String>,
+    /// speedrun.com `ID` of the game, if linked.
+    pub srdc_id: Option<String>,
+}
+
+/// A category on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsCategory {
+    /// splits.io `ID` of the category.
+    pub id: String,
+    /// Name of the category.
+    pub name: String,
+    /// speedrun.com `ID` of the category, if linked.
+    pub srdc_id: Option<String>,
+}
+
+/// A runner on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsRunner {
+    /// splits.io `ID` of the runner.
+    pub id: String,
+    /// Username of the runner.
+    pub name: String,
+    /// Display name of the runner.
+    pub display_name: Option<String>,
+    /// Twitch `ID` of the runner.
+    pub twitch_id: Option<String>,
+}
+
+/// A segment of a run on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsSegment {
+    /// splits.io `ID` of the segment.
+    pub id: String,
+    /// Position of the segment in the run, starting at 0.
+    pub segment_number: i64,
+    /// Name of the segment.
+    pub name: String,
+    /// Real time at the start of the segment in milliseconds.
+    pub realtime_start_ms: Option<i64>,
+    /// Real time duration of the segment in milliseconds.
+    pub realtime_duration_ms: Option<i64>,
+    /// Real time at the end of the segment in milliseconds.
+    pub realtime_end_ms: Option<i64>,
+    /// Best real time duration of the segment in milliseconds.
+    pub realtime_shortest_duration_ms: Option<i64>,
+    /// If the segment was a real time gold (best segment).
+    #[serde(default)]
+    pub realtime_gold: bool,
+    /// If the segment was skipped in real time.
+    #[serde(default)]
+    pub realtime_skipped: bool,
+    /// Game time at the start of the segment in milliseconds.
+    pub gametime_start_ms: Option<i64>,
+    /// Game time duration of the segment in milliseconds.
+    pub gametime_duration_ms: Option<i64>,
+    /// Game time at the end of the segment in milliseconds.
+    pub gametime_end_ms: Option<i64>,
+    /// Best game time duration of the segment in milliseconds.
+    pub gametime_shortest_duration_ms: Option<i64>,
+    /// If the segment was a game time gold (best segment).
+    #[serde(default)]
+    pub gametime_gold: bool,
+    /// If the segment was skipped in game time.
+    #[serde(default)]
+    pub gametime_skipped: bool,
+    /// Segment history, only included when requested.
+    #[serde(default)]
+    pub histories: Vec<SplitsAttempt>,
+}
+
+/// A single attempt of a run or segment on splits.io.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SplitsAttempt {
+    /// Number of the attempt.
+    pub attempt_number: i64,
+    /// Real time duration in milliseconds.
+    pub realtime_duration_ms: Option<i64>,
+    /// Game time duration in milliseconds.
+    pub gametime_duration_ms: Option<i64>,
+    /// When the attempt was started.
+    pub started_at: Option<String>,
+    /// When the attempt ended.
+    pub ended_at: Option<String>,
+}
+
+/// Retrieves the splits (with history) attached to a speedrun.com run.
+///
+/// Returns `None` if the run has no splits.
+pub fn run_splits<C>(
+    client: &C,
+    run: &types::Run<'_>,
+) -> Result<Option<SplitsRun>, ApiError<C::Error>>
+where
+    C: Client,
+{
+    match RunSplits::for_run(run) {
+        Some(endpoint) => endpoint.query(client).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Retrieves the splits (with history) attached to a speedrun.com run
+/// asynchronously.
+///
+/// Returns `None` if the run has no splits.
+pub async fn run_splits_async<C>(
+    client: &C,
+    run: &types::Run<'_>,
+) -> Result<Option<SplitsRun>, ApiError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    match RunSplits::for_run(run) {
+        Some(endpoint) => endpoint.query_async(client).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The splits.io `ID` from a splits link (e.g. `https://splits.io/api/v4/runs/7t6`).
+fn splits_id(link: &types::Link) -> Option<&str> {
+    let path = link.uri.split(['?', '#']).next()?;
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|id| !id.is_empty() && !id.contains(':'))
+}
+
+impl From<&api::runs::SplitsIo> for RunSplits<'static> {
+    fn from(value: &api::runs::SplitsIo) -> Self {
+        let id = match value {
+            api::runs::SplitsIo::Id(id) => id.clone(),
+            api::runs::SplitsIo::Url(url) => url
+                .path_segments()
+                .and_then(|mut segments| segments.rfind(|segment| !segment.is_empty()))
+                .unwrap_or_default()
+                .to_owned(),
+        };
+        Self {
+            id: Cow::Owned(id),
+            historic: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::{Link, LinkRel};
+
+    #[test]
+    fn splits_link_id() {
+        let link = Link {
+            rel: LinkRel::SplitsIo,
+            uri: "https://splits.io/api/v3/runs/7t6".into(),
+        };
+        assert_eq!(splits_id(&link), Some("7t6"));
+    }
+
+    #[test]
+    fn deserialize_run() {
+        let root: RunRoot<SplitsRun> = serde_json::from_value(json!({
+            "run": {
+                "id": "7t6",
+                "srdc_id": null,
+                "realtime_duration_ms": 4263500,
+                "default_timing": "real",
+                "program": "livesplit",
+                "game": { "id": "1", "name": "Super Mario Sunshine", "shortname": "sms" },
+                "category": { "id": "2", "name": "Any%" },
+                "runners": [{ "id": "3", "name": "runner" }],
+                "segments": [{
+                    "id": "4",
+                    "segment_number": 0,
+                    "name": "Bianco Hills",
+                    "realtime_duration_ms": 1800000,
+                    "realtime_gold": true,
+                    "histories": [{ "attempt_number": 1, "realtime_duration_ms": 1860000 }],
+                }],
+            }
+        }))
+        .unwrap();
+
+        let run = root.run;
+        assert_eq!(run.game.unwrap().name, "Super Mario Sunshine");
+        assert_eq!(
+            run.segments[0].histories[0].realtime_duration_ms,
+            Some(1860000)
+        );
+        assert!(run.histories.is_empty());
+    }
+
+    #[test]
+    fn base_url() {
+        let client = SplitsIoBuilder::new()
+            .base_url("http://localhost:8080/api/v4")
+            .build()
+            .unwrap();
+        assert_eq!(
+            client.rest_endpoint("runs/7t6").unwrap().as_str(),
+            "http://localhost:8080/api/v4/runs/7t6"
+        );
+    }
+}
@@ -0,0 +1,815 @@
+//! # SQLite
+//!
+//! A local mirror of games in a SQLite database. Requires the `sqlite`
+//! feature.
+//!
+//! [`Mirror::sync`] stores a game with its categories, levels, variables and
+//! values, the runs submitted or verified since the previous sync of the game
+//! and the users referenced by those runs. Every row keeps the resource as
+//! returned by the API in its `data` column, next to the columns used for
+//! queries.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{
+//!     analysis::Leaderboard, api::categories::CategoryId, sqlite::Mirror, SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//! let mut mirror = Mirror::open("mirror.sqlite")?;
+//! let report = mirror.sync_async(&client, "smw").await?;
+//! println!("{} runs submitted since the last sync", report.submitted);
+//!
+//! // Rank the runs of a category without any further requests.
+//! let category = CategoryId::new("7dgrrxk4");
+//! let board = Leaderboard::builder().category(category.clone()).build()?;
+//! for ranked in board.rank(mirror.runs(&category, None)?) {
+//!     println!("{}. {}", ranked.place, ranked.run.times.primary);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::path::Path;
+
+use futures::TryStreamExt;
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        games::{Game, GameEmbeds, GameId},
+        levels::LevelId,
+        runs::{RunStatus, Runs, RunsSorting},
+        users::User,
+        ApiError, AsyncClient, AsyncQuery, Client, Direction, PagedEndpointExt, Query,
+    },
+    types::{self, Player, Scope, Status},
+};
+
+/// Number of runs written per transaction.
+const BATCH_SIZE: usize = 200;
+
+/// Tables of a mirror. `data` columns hold the JSON returned by the API.
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS games (
+    id TEXT PRIMARY KEY,
+    abbreviation TEXT NOT NULL,
+    name TEXT NOT NULL,
+    weblink TEXT NOT NULL,
+    release_date TEXT,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS categories (
+    id TEXT PRIMARY KEY,
+    game TEXT NOT NULL,
+    name TEXT NOT NULL,
+    per_level INTEGER NOT NULL,
+    miscellaneous INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS levels (
+    id TEXT PRIMARY KEY,
+    game TEXT NOT NULL,
+    name TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS variables (
+    id TEXT PRIMARY KEY,
+    game TEXT NOT NULL,
+    category TEXT,
+    level TEXT,
+    name TEXT NOT NULL,
+    scope TEXT NOT NULL,
+    mandatory INTEGER NOT NULL,
+    is_subcategory INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS variable_values (
+    variable TEXT NOT NULL,
+    id TEXT NOT NULL,
+    label TEXT NOT NULL,
+    miscellaneous INTEGER NOT NULL,
+    PRIMARY KEY (variable, id)
+);
+CREATE TABLE IF NOT EXISTS runs (
+    id TEXT PRIMARY KEY,
+    game TEXT NOT NULL,
+    category TEXT NOT NULL,
+    level TEXT,
+    status TEXT NOT NULL,
+    examiner TEXT,
+    verify_date TEXT,
+    reason TEXT,
+    date TEXT,
+    submitted TEXT,
+    primary_t REAL NOT NULL,
+    realtime_t REAL,
+    realtime_noloads_t REAL,
+    ingame_t REAL,
+    platform TEXT,
+    region TEXT,
+    emulated INTEGER NOT NULL,
+    weblink TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS runs_board ON runs (category, level, status);
+CREATE TABLE IF NOT EXISTS run_players (
+    run TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    user TEXT,
+    guest TEXT,
+    PRIMARY KEY (run, position)
+);
+CREATE INDEX IF NOT EXISTS run_players_user ON run_players (user);
+CREATE TABLE IF NOT EXISTS run_values (
+    run TEXT NOT NULL,
+    variable TEXT NOT NULL,
+    value TEXT NOT NULL,
+    PRIMARY KEY (run, variable)
+);
+CREATE TABLE IF NOT EXISTS users (
+    id TEXT PRIMARY KEY,
+    name TEXT,
+    weblink TEXT,
+    signup TEXT,
+    data TEXT
+);
+CREATE TABLE IF NOT EXISTS sync (
+    game TEXT PRIMARY KEY,
+    submitted TEXT,
+    verified TEXT
+);
+"#;
+
+/// Errors that occur when syncing a mirror.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SyncError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// Error from the database.
+    #[error("database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    /// The API returned a game that could not be parsed.
+    #[error("could not parse game: {0}")]
+    Game(#[from] serde_json::Error),
+}
+
+/// Number of resources stored by a sync.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    /// Runs stored because they were submitted since the previous sync.
+    pub submitted: usize,
+    /// Runs stored because they were verified since the previous sync.
+    pub verified: usize,
+    /// Users stored because they were referenced for the first time.
+    pub users: usize,
+}
+
+/// A SQLite database mirroring one or more games.
+#[derive(Debug)]
+pub struct Mirror {
+    conn: Connection,
+}
+
+/// Position of the previous sync of a game.
+#[derive(Debug, Default)]
+struct Marks {
+    submitted: Option<String>,
+    verified: Option<String>,
+}
+
+/// Runs found by one pass over the runs of a game, newest first.
+struct Pass {
+    /// Newest timestamp seen by the previous sync.
+    since: Option<String>,
+    /// Newest timestamp seen by this pass.
+    newest: Option<String>,
+    timestamp: fn(&types::Run<'_>) -> Option<String>,
+    runs: Vec<(Value, types::Run<'static>)>,
+}
+
+impl Mirror {
+    /// Open (or create) the database at `path`.
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    /// Use `conn` as the database, creating the tables if they do not exist.
+    pub fn new(conn: Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    /// The underlying connection, for queries.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Close the mirror, returning the underlying connection.
+    pub fn into_inner(self) -> Connection {
+        self.conn
+    }
+
+    /// `ID`s of the mirrored games.
+    pub fn games(&self) -> rusqlite::Result<Vec<GameId<'static>>> {
+        let mut stmt = self.conn.prepare("SELECT id FROM games ORDER BY id")?;
+        let ids = stmt.query_map([], |row| row.get::<_, String>(0))?;
+        ids.map(|id| id.map(GameId::new)).collect()
+    }
+
+    /// Verified runs of `category` (and `level` for individual levels), e.g.
+    /// for [`crate::analysis::Leaderboard::rank`].
+    pub fn runs(
+        &self,
+        category: &CategoryId<'_>,
+        level: Option<&LevelId<'_>>,
+    ) -> rusqlite::Result<Vec<types::Run<'static>>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT data FROM runs WHERE category = ?1 AND level IS ?2 AND status = 'verified' \
+             ORDER BY date, submitted",
+        )?;
+        let rows = stmt.query_map(
+            params![category.to_string(), level.map(ToString::to_string)],
+            |row| row.get::<_, String>(0),
+        )?;
+        let mut runs = Vec::new();
+        for data in rows {
+            if let Ok(run) = serde_json::from_str(&data?) {
+                runs.push(run);
+            }
+        }
+        Ok(runs)
+    }
+
+    /// Sync `game` (an `ID` or abbreviation) using `client`.
+    ///
+    /// The first sync of a game stores all of its runs. Later syncs store the
+    /// runs submitted or verified since, until the previous sync is reached.
+    /// Runs rejected or deleted after they were stored are not updated.
+    pub fn sync<C>(&mut self, client: &C, game: &str) -> Result<SyncReport, SyncError<C::Error>>
+    where
+        C: Client,
+    {
+        let game = self.store_game(&game_endpoint(game).query(client)?)?;
+        let marks = self.marks(&game)?;
+        let first = marks.submitted.is_none() && marks.verified.is_none();
+        let mut report = SyncReport::default();
+
+        let endpoint = runs_endpoint(&game, RunsSorting::Submitted);
+        let mut submitted = Pass::new(marks.submitted, submitted_at);
+        for run in endpoint.iter::<Value, _>(client) {
+            if !submitted.add(run?) {
+                break;
+            }
+            if submitted.runs.len() >= BATCH_SIZE {
+                report.submitted += self.store_runs(&mut submitted.runs)?;
+            }
+        }
+        report.submitted += self.store_runs(&mut submitted.runs)?;
+
+        let endpoint = runs_endpoint(&game, RunsSorting::VerifyDate);
+        let mut verified = Pass::new(marks.verified, verified_at);
+        if !first {
+            for run in endpoint.iter::<Value, _>(client) {
+                if !verified.add(run?) {
+                    break;
+                }
+                if verified.runs.len() >= BATCH_SIZE {
+                    report.verified += self.store_runs(&mut verified.runs)?;
+                }
+            }
+            report.verified += self.store_runs(&mut verified.runs)?;
+        }
+
+        for id in self.missing_users()? {
+            let user = match user_endpoint(&id).query(client) {
+                Ok(user) => Some(user),
+                Err(err) if err.is_not_found() => None,
+                Err(err) => return Err(err.into()),
+            };
+            self.store_user(&id, user.as_ref())?;
+            report.users += 1;
+        }
+
+        self.finish(&game, first, submitted, verified)?;
+        Ok(report)
+    }
+
+    /// Sync `game` (an `ID` or abbreviation) asynchronously using `client`.
+    ///
+    /// See [`Mirror::sync`].
+    pub async fn sync_async<C>(
+        &mut self,
+        client: &C,
+        game: &str,
+    ) -> Result<SyncReport, SyncError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let game = self.store_game(&game_endpoint(game).query_async(client).await?)?;
+        let marks = self.marks(&game)?;
+        let first = marks.submitted.is_none() && marks.verified.is_none();
+        let mut report = SyncReport::default();
+
+        let endpoint = runs_endpoint(&game, RunsSorting::Submitted);
+        let mut submitted = Pass::new(marks.submitted, submitted_at);
+        let mut runs = endpoint.stream::<Value, _>(client);
+        while let Some(run) = runs.try_next().await? {
+            if !submitted.add(run) {
+                break;
+            }
+            if submitted.runs.len() >= BATCH_SIZE {
+                report.submitted += self.store_runs(&mut submitted.runs)?;
+            }
+        }
+        drop(runs);
+        report.submitted += self.store_runs(&mut submitted.runs)?;
+
+        let endpoint = runs_endpoint(&game, RunsSorting::VerifyDate);
+        let mut verified = Pass::new(marks.verified, verified_at);
+        if !first {
+            let mut runs = endpoint.stream::<Value, _>(client);
+            while let Some(run) = runs.try_next().await? {
+                if !verified.add(run) {
+                    break;
+                }
+                if verified.runs.len() >= BATCH_SIZE {
+                    report.verified += self.store_runs(&mut verified.runs)?;
+                }
+            }
+            drop(runs);
+            report.verified += self.store_runs(&mut verified.runs)?;
+        }
+
+        for id in self.missing_users()? {
+            let user = match user_endpoint(&id).query_async(client).await {
+                Ok(user) => Some(user),
+                Err(err) if err.is_not_found() => None,
+                Err(err) => return Err(err.into()),
+            };
+            self.store_user(&id, user.as_ref())?;
+            report.users += 1;
+        }
+
+        self.finish(&game, first, submitted, verified)?;
+        Ok(report)
+    }
+
+    /// Store `game` with its embedded categories, levels and variables,
+    /// replacing the previous ones.
+    fn store_game<E>(&mut self, game: &Value) -> Result<GameId<'static>, SyncError<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let parsed: types::Game<'static> = serde_json::from_value(game.clone())?;
+        let id = parsed.id.to_string();
+        let mut data = game.clone();
+        if let Some(fields) = data.as_object_mut() {
+            for embed in ["categories", "levels", "variables"] {
+                fields.remove(embed);
+            }
+        }
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO games (id, abbreviation, name, weblink, release_date, data) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                id,
+                parsed.abbreviation,
+                parsed.names.international,
+                parsed.weblink,
+                parsed.release_date,
+                data.to_string(),
+            ],
+        )?;
+
+        tx.execute("DELETE FROM categories WHERE game = ?1", [&id])?;
+        for (value, category) in embedded::<types::Category<'static>>(game, "categories") {
+            tx.execute(
+                "INSERT OR REPLACE INTO categories (id, game, name, per_level, miscellaneous, data) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    category.id.to_string(),
+                    id,
+                    category.name,
+                    matches!(category.type_field, types::CategoryType::PerLevel),
+                    category.miscellaneous,
+                    value.to_string(),
+                ],
+            )?;
+        }
+
+        tx.execute("DELETE FROM levels WHERE game = ?1", [&id])?;
+        for (value, level) in embedded::<types::Level<'static>>(game, "levels") {
+            tx.execute(
+                "INSERT OR REPLACE INTO levels (id, game, name, data) VALUES (?1, ?2, ?3, ?4)",
+                params![level.id.to_string(), id, level.name, value.to_string()],
+            )?;
+        }
+
+        tx.execute(
+            "DELETE FROM variable_values WHERE variable IN \
+             (SELECT id FROM variables WHERE game = ?1)",
+            [&id],
+        )?;
+        tx.execute("DELETE FROM variables WHERE game = ?1", [&id])?;
+        for (value, variable) in embedded::<types::Variable<'static>>(game, "variables") {
+            let (scope, level) = match &variable.scope {
+                Scope::Global => ("global", None),
+                Scope::FullGame => ("full-game", None),
+                Scope::AllLevels => ("all-levels", None),
+                Scope::SingleLevel { level } => ("single-level", Some(level)),
+            };
+            let variable_id = variable.id.to_string();
+            tx.execute(
+                "INSERT OR REPLACE INTO variables \
+                 (id, game, category, level, name, scope, mandatory, is_subcategory, data) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    variable_id,
+                    id,
+                    variable.category.as_ref().map(ToString::to_string),
+                    level,
+                    variable.name,
+                    scope,
+                    variable.mandatory,
+                    variable.is_subcategory,
+                    value.to_string(),
+                ],
+            )?;
+            for (value_id, value) in &variable.values.values {
+                tx.execute(
+                    "INSERT OR REPLACE INTO variable_values (variable, id, label, miscellaneous) \
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        variable_id,
+                        value_id.to_string(),
+                        value.label,
+                        value
+                            .flags
+                            .as_ref()
+                            .and_then(|flags| flags.miscellaneous)
+                            .unwrap_or_default(),
+                    ],
+                )?;
+            }
+        }
+        tx.commit()?;
+
+        Ok(GameId::new(id))
+    }
+
+    /// Store `runs` in a single transaction, leaving `runs` empty.
+    fn store_runs(
+        &mut self,
+        runs: &mut Vec<(Value, types::Run<'static>)>,
+    ) -> rusqlite::Result<usize> {
+        let tx = self.conn.transaction()?;
+        for (value, run) in runs.iter() {
+            store_run(&tx, value, run)?;
+        }
+        tx.commit()?;
+        let stored = runs.len();
+        runs.clear();
+        Ok(stored)
+    }
+
+    /// Store the user `id`. `None` records a user that no longer exists.
+    fn store_user(&self, id: &str, user: Option<&Value>) -> rusqlite::Result<()> {
+        let field = |pointer: &str| {
+            user.and_then(|user| user.pointer(pointer))
+                .and_then(Value::as_str)
+                .map(ToOwned::to_owned)
+        };
+        self.conn.execute(
+            "INSERT OR REPLACE INTO users (id, name, weblink, signup, data) \
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                id,
+                field("/names/international"),
+                field("/weblink"),
+                field("/signup"),
+                user.map(ToString::to_string),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// `ID`s of players and examiners that are not stored yet.
+    fn missing_users(&self) -> rusqlite::Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT user FROM run_players WHERE user IS NOT NULL \
+             UNION SELECT examiner FROM runs WHERE examiner IS NOT NULL \
+             EXCEPT SELECT id FROM users",
+        )?;
+        let ids = stmt.query_map([], |row| row.get(0))?;
+        ids.collect()
+    }
+
+    fn marks(&self, game: &GameId<'_>) -> rusqlite::Result<Marks> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT submitted, verified FROM sync WHERE game = ?1",
+                [game.to_string()],
+                |row| {
+                    Ok(Marks {
+                        submitted: row.get(0)?,
+                        verified: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?
+            .unwrap_or_default())
+    }
+
+    /// Record the position of a completed sync of `game`.
+    fn finish(
+        &self,
+        game: &GameId<'_>,
+        first: bool,
+        submitted: Pass,
+        verified: Pass,
+    ) -> rusqlite::Result<()> {
+        let game = game.to_string();
+        let verified = if first {
+            // The first sync only looks at submissions, which include every
+            // verified run.
+            self.conn.query_row(
+                "SELECT MAX(verify_date) FROM runs WHERE game = ?1",
+                [&game],
+                |row| row.get(0),
+            )?
+        } else {
+            verified.newest.or(verified.since)
+        };
+        self.conn.execute(
+            "INSERT OR REPLACE INTO sync (game, submitted, verified) VALUES (?1, ?2, ?3)",
+            params![game, submitted.newest.or(submitted.since), verified],
+        )?;
+        Ok(())
+    }
+}
+
+impl Pass {
+    fn new(since: Option<String>, timestamp: fn(&types::Run<'_>) -> Option<String>) -> Self {
+        Self {
+            since,
+            newest: None,
+            timestamp,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Add `run`, returning `false` once runs are older than the previous
+    /// sync. Runs that cannot be parsed are skipped.
+    fn add(&mut self, run: Value) -> bool {
+        let Ok(parsed) = serde_json::from_value::<types::Run<'static>>(run.clone()) else {
+            return true;
+        };
+        if let Some(at) = (self.timestamp)(&parsed) {
+            if self.since.as_ref().is_some_and(|since| at < *since) {
+                return false;
+            }
+            if self.newest.as_ref() < Some(&at) {
+                self.newest = Some(at);
+            }
+        }
+        self.runs.push((run, parsed));
+        true
+    }
+}
+
+fn store_run(tx: &Transaction<'_>, value: &Value, run: &types::Run<'_>) -> rusqlite::Result<()> {
+    let id = run.id.to_string();
+    let (status, examiner, verify_date, reason) = match &run.status {
+        Status::New => ("new", None, None, None),
+        Status::Verified {
+            examiner,
+            verify_date,
+        } => (
+            "verified",
+            examiner.as_ref().map(ToString::to_string),
+            verify_date.as_deref(),
+            None,
+        ),
+        Status::Rejected { examiner, reason } => {
+            ("rejected", Some(examiner.to_string()), None, Some(reason))
+        }
+    };
+    let times = &run.times;
+    tx.execute(
+        "INSERT OR REPLACE INTO runs (id, game, category, level, status, examiner, verify_date, \
+         reason, date, submitted, primary_t, realtime_t, realtime_noloads_t, ingame_t, platform, \
+         region, emulated, weblink, data) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+        params![
+            id,
+            run.game.to_string(),
+            run.category.to_string(),
+            run.level.as_ref().map(ToString::to_string),
+            status,
+            examiner,
+            verify_date,
+            reason,
+            run.date,
+            run.submitted,
+            times.primary_t,
+            times.realtime.as_ref().map(|_| times.realtime_t),
+            times.realtime_noloads.as_ref().map(|_| times.realtime_noloads_t),
+            times.ingame.as_ref().map(|_| times.ingame_t),
+            run.system.platform.as_ref().map(ToString::to_string),
+            run.system.region.as_ref().map(ToString::to_string),
+            run.system.emulated,
+            run.weblink,
+            value.to_string(),
+        ],
+    )?;
+
+    tx.execute("DELETE FROM run_players WHERE run = ?1", [&id])?;
+    for (position, player) in run.players.iter().enumerate() {
+        let (user, guest) = match player {
+            Player::User { id, .. } => (Some(id.to_string()), None),
+            Player::Guest { name, .. } => (None, Some(name)),
+        };
+        tx.execute(
+            "INSERT INTO run_players (run, position, user, guest) VALUES (?1, ?2, ?3, ?4)",
+            params![id, position, user, guest],
+        )?;
+    }
+
+    tx.execute("DELETE FROM run_values WHERE run = ?1", [&id])?;
+    for (variable, value) in &run.values {
+        tx.execute(
+            "INSERT INTO run_values (run, variable, value) VALUES (?1, ?2, ?3)",
+            params![id, variable.to_string(), value],
+        )?;
+    }
+    Ok(())
+}
+
+/// Parseable resources embedded in `field` of `value`, with their JSON.
+fn embedded<T: DeserializeOwned>(value: &Value, field: &str) -> Vec<(Value, T)> {
+    value
+        .get(field)
+        .and_then(|embed| embed.get("data"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| Some((item.clone(), serde_json::from_value(item.clone()).ok()?)))
+        .collect()
+}
+
+fn submitted_at(run: &types::Run<'_>) -> Option<String> {
+    run.submitted.clone()
+}
+
+fn verified_at(run: &types::Run<'_>) -> Option<String> {
+    match &run.status {
+        Status::Verified { verify_date, .. } => verify_date.clone(),
+        _ => None,
+    }
+}
+
+fn game_endpoint(game: &str) -> Game<'_> {
+    Game::builder()
+        .id(game)
+        .embeds(
+            [
+                GameEmbeds::Categories,
+                GameEmbeds::Levels,
+                GameEmbeds::Variables,
+            ]
+            .into_iter(),
+        )
+        .build()
+        .expect("all required fields are set")
+}
+
+/// Runs of `game`, newest first by `orderby`.
+fn runs_endpoint<'a>(game: &GameId<'a>, orderby: RunsSorting) -> Runs<'a> {
+    let mut builder = Runs::builder();
+    builder
+        .game(game.clone())
+        .orderby(orderby)
+        .direction(Direction::Desc);
+    if matches!(orderby, RunsSorting::VerifyDate) {
+        builder.status(RunStatus::Verified);
+    }
+    builder.build().expect("all required fields are set")
+}
+
+fn user_endpoint(id: &str) -> User<'_> {
+    User::builder()
+        .id(id)
+        .build()
+        .expect("all required fields are set")
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        fixtures,
+        mock::{self, MockClient},
+    };
+
+    fn run(id: &str, submitted: &str, player: &str) -> Value {
+        let mut run = fixtures::run_json(id, player, 60.0, "2021-01-01");
+        run["game"] = json!("game");
+        run["status"] =
+            json!({ "status": "verified", "examiner": "mod", "verify-date": submitted });
+        run["submitted"] = json!(submitted);
+        run["system"]["platform"] = json!("p");
+        run["values"] = json!({ "v": "x" });
+        run
+    }
+
+    #[test]
+    fn store_and_query_runs() {
+        let mut mirror = Mirror::new(Connection::open_in_memory().unwrap()).unwrap();
+        let mut pass = Pass::new(Some("2021-01-02T00:00:00Z".into()), submitted_at);
+        assert!(pass.add(run("b", "2021-01-03T00:00:00Z", "u2")));
+        assert!(pass.add(run("a", "2021-01-02T00:00:00Z", "u1")));
+        assert!(!pass.add(run("z", "2021-01-01T00:00:00Z", "u0")));
+        assert_eq!(pass.newest.as_deref(), Some("2021-01-03T00:00:00Z"));
+        assert_eq!(mirror.store_runs(&mut pass.runs).unwrap(), 2);
+
+        let runs = mirror.runs(&CategoryId::new("c"), None).unwrap();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(
+            mirror.missing_users().unwrap(),
+            ["mod", "u1", "u2"].map(String::from)
+        );
+        mirror.store_user("mod", None).unwrap();
+        assert_eq!(
+            mirror.missing_users().unwrap(),
+            ["u1", "u2"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn sync_pages_of_runs() {
+        // Newest first, over three pages of 20 runs.
+        let runs: Vec<Value> = (0..45)
+            .rev()
+            .map(|n| {
+                let submitted = format!("2021-01-01T00:00:{n:02}Z");
+                run(&format!("r{n}"), &submitted, &format!("u{}", n % 3))
+            })
+            .collect();
+        let client = MockClient::new(move |request| match request.path() {
+            "games/game" => Some(fixtures::game_json()),
+            "runs" => Some(mock::page(request, &runs)),
+            path => path
+                .strip_prefix("users/")
+                .map(|id| json!({ "id": id, "names": { "international": id } })),
+        });
+        let run_offsets = |client: &MockClient| -> Vec<String> {
+            client
+                .requests()
+                .iter()
+                .filter(|request| request.path() == "runs")
+                .inspect(|request| assert_eq!(request.query("game").as_deref(), Some("game")))
+                .filter_map(|request| request.query("offset"))
+                .collect()
+        };
+
+        let mut mirror = Mirror::new(Connection::open_in_memory().unwrap()).unwrap();
+        let report = mirror.sync(&client, "game").unwrap();
+        assert_eq!(
+            report,
+            SyncReport {
+                submitted: 45,
+                verified: 0,
+                users: 4,
+            }
+        );
+        assert_eq!(run_offsets(&client), ["0", "20", "40"]);
+        assert_eq!(mirror.runs(&CategoryId::new("c"), None).unwrap().len(), 45);
+
+        // Only the newest run of each pass is at or after the previous sync.
+        let report = mirror.sync(&client, "game").unwrap();
+        assert_eq!(
+            report,
+            SyncReport {
+                submitted: 1,
+                verified: 1,
+                users: 0,
+            }
+        );
+        assert_eq!(run_offsets(&client), ["0", "20", "40", "0", "0"]);
+    }
+}
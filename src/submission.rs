@@ -0,0 +1,825 @@
+//! # Submission
+//!
+//! Pre-flight checks for submitting runs with [`CreateRun`].
+//!
+//! [`validate`] and [`validate_async`] retrieve the game, category and
+//! variables a run is submitted to, and report every problem that would
+//! cause the submission to be rejected.
+//!
+//! [`find_duplicates`] reports runs that were already submitted with the same
+//! times, and [`submit`] records submitted runs in a [`SubmissionStore`] so
+//! that retrying a submission does not create another run.
+//!
+//! ```rust ,no_run
+//! use speedrun_api::{api::runs::CreateRun, submission, SpeedrunApiBuilder};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().api_key("KEY").build_async()?;
+//!
+//! let run = CreateRun::buider()
+//!     .category("n2y5jzd3")
+//!     .realtime(4263.0)
+//!     .emulated(false)
+//!     .build()?;
+//! for problem in submission::validate_async(&client, &run).await? {
+//!     println!("{problem}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use futures::TryStreamExt;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::{Category, CategoryEmbeds, CategoryId, CategoryVariables},
+        levels::LevelId,
+        platforms::PlatformId,
+        profile::Profile,
+        regions::RegionId,
+        runs::{CreateRun, Player, RunId, Runs, ValueType},
+        variables::{ValueId, VariableId},
+        ApiError, AsyncClient, AsyncQuery, Client, PagedEndpointExt, Query,
+    },
+    types::{self, CategoryType, Players, Root, Scope, TimingMethod},
+};
+
+/// A problem with a run that would cause its submission to be rejected.
+#[derive(Debug, Clone, PartialEq, Error)]
+#[non_exhaustive]
+pub enum Problem {
+    /// None of the run times are set.
+    #[error("no time is set for the run")]
+    MissingTime,
+    /// A time is set for a timing method the game does not use.
+    #[error("the game does not use the {0:?} timing method")]
+    TimingMethodNotAllowed(TimingMethod),
+    /// The game requires a video, but none is set.
+    #[error("the game requires a video")]
+    MissingVideo,
+    /// The run was done on an emulator, but the game does not allow emulators.
+    #[error("the game does not allow emulators")]
+    EmulatorNotAllowed,
+    /// The category requires a different number of players.
+    #[error(
+        "the category requires {} player(s), but the run has {actual}",
+        describe_players(expected)
+    )]
+    PlayerCount {
+        /// Number of players required by the category.
+        expected: Players,
+        /// Number of players of the run.
+        actual: usize,
+    },
+    /// The category is a per-level category, but no level is set.
+    #[error("category `{0}` is a per-level category, but no level is set")]
+    MissingLevel(CategoryId<'static>),
+    /// The category is a full-game category, but a level is set.
+    #[error("category `{0}` is a full-game category, but level `{1}` is set")]
+    UnexpectedLevel(CategoryId<'static>, LevelId<'static>),
+    /// The platform is not available for the game.
+    #[error("platform `{0}` is not available for the game")]
+    InvalidPlatform(PlatformId<'static>),
+    /// The region is not available for the game.
+    #[error("region `{0}` is not available for the game")]
+    InvalidRegion(RegionId<'static>),
+    /// A mandatory variable is not set.
+    #[error("mandatory variable `{name}` ({id}) is not set")]
+    MissingVariable {
+        /// `ID` of the variable.
+        id: VariableId<'static>,
+        /// Name of the variable.
+        name: String,
+    },
+    /// A variable is set that does not apply to the run.
+    #[error("variable `{0}` does not apply to this run")]
+    UnknownVariable(VariableId<'static>),
+    /// The value of a variable is not one of its pre-defined values.
+    #[error("`{value}` is not a value of variable `{name}` ({id})")]
+    InvalidValue {
+        /// `ID` of the variable.
+        id: VariableId<'static>,
+        /// Name of the variable.
+        name: String,
+        /// The invalid value.
+        value: String,
+    },
+    /// A free text value is set for a variable that only has pre-defined
+    /// values.
+    #[error("variable `{name}` ({id}) does not accept user-defined values")]
+    UserDefinedValue {
+        /// `ID` of the variable.
+        id: VariableId<'static>,
+        /// Name of the variable.
+        name: String,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct CategoryWithGame {
+    #[serde(flatten)]
+    category: types::Category<'static>,
+    game: Root<types::Game<'static>>,
+}
+
+/// Checks `run` against the ruleset of `game`, the `category` it is submitted
+/// to and the `variables` of the category.
+///
+/// Returns every problem found, an empty list means the run is expected to be
+/// accepted. The platform and region are only checked if the game lists
+/// platforms and regions respectively.
+pub fn check(
+    run: &CreateRun<'_>,
+    game: &types::Game<'_>,
+    category: &types::Category<'_>,
+    variables: &[types::Variable<'_>],
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let ruleset = &game.ruleset;
+
+    let times = [
+        (TimingMethod::Realtime, run.times.realtime),
+        (TimingMethod::RealtimeNoloads, run.times.realtime_noloads),
+        (TimingMethod::Ingame, run.times.ingame),
+    ];
+    if times.iter().all(|(_, time)| time.is_none()) {
+        problems.push(Problem::MissingTime);
+    }
+    problems.extend(
+        times
+            .into_iter()
+            .filter(|(method, time)| time.is_some() && !ruleset.run_times.contains(method))
+            .map(|(method, _)| Problem::TimingMethodNotAllowed(method)),
+    );
+
+    if ruleset.require_video && run.video.is_none() {
+        problems.push(Problem::MissingVideo);
+    }
+    if !ruleset.emulators_allowed && run.emulated == Some(true) {
+        problems.push(Problem::EmulatorNotAllowed);
+    }
+
+    // Without any players, the run is submitted for the authenticated user.
+    let players = run.players.len().max(1);
+    let players_ok = match category.players {
+        Players::Exactly(expected) => players as i64 == expected,
+        Players::UpTo(max) => players as i64 <= max,
+    };
+    if !players_ok {
+        problems.push(Problem::PlayerCount {
+            expected: category.players.clone(),
+            actual: players,
+        });
+    }
+
+    match (&category.type_field, &run.level) {
+        (CategoryType::PerLevel, None) => {
+            problems.push(Problem::MissingLevel(owned_category_id(&category.id)))
+        }
+        (CategoryType::PerGame, Some(level)) => problems.push(Problem::UnexpectedLevel(
+            owned_category_id(&category.id),
+            LevelId::new(level.to_string()),
+        )),
+        _ => {}
+    }
+
+    if let Some(platform) = &run.platform {
+        if !game.platforms.is_empty() && !game.platforms.contains(platform) {
+            problems.push(Problem::InvalidPlatform(PlatformId::new(
+                platform.to_string(),
+            )));
+        }
+    }
+    if let Some(region) = &run.region {
+        if !game.regions.is_empty() && !game.regions.contains(region) {
+            problems.push(Problem::InvalidRegion(RegionId::new(region.to_string())));
+        }
+    }
+
+    let applicable: Vec<_> = variables
+        .iter()
+        .filter(|variable| applies_to(variable, &run.category, run.level.as_ref()))
+        .collect();
+    for variable in &applicable {
+        match run.variables.get(&variable.id) {
+            None if variable.mandatory => problems.push(Problem::MissingVariable {
+                id: owned_variable_id(&variable.id),
+                name: variable.name.clone(),
+            }),
+            Some(ValueType::PreDefined { value }) if !is_value_of(variable, value) => problems
+                .push(Problem::InvalidValue {
+                    id: owned_variable_id(&variable.id),
+                    name: variable.name.clone(),
+                    value: value.to_string(),
+                }),
+            Some(ValueType::UserDefined { .. }) if !variable.user_defined => {
+                problems.push(Problem::UserDefinedValue {
+                    id: owned_variable_id(&variable.id),
+                    name: variable.name.clone(),
+                })
+            }
+            _ => {}
+        }
+    }
+    problems.extend(
+        run.variables
+            .keys()
+            .filter(|id| !applicable.iter().any(|variable| &variable.id == *id))
+            .map(|id| Problem::UnknownVariable(owned_variable_id(id))),
+    );
+
+    problems
+}
+
+/// Retrieves the game, category and variables for `run` and checks it with
+/// [`check`].
+pub fn validate<C>(client: &C, run: &CreateRun<'_>) -> Result<Vec<Problem>, ApiError<C::Error>>
+where
+    C: Client,
+{
+    let (category_endpoint, variables_endpoint) = endpoints(&run.category);
+    let CategoryWithGame { category, game } = category_endpoint.query(client)?;
+    let variables: Vec<types::Variable<'static>> = variables_endpoint.query(client)?;
+
+    Ok(check(run, &game.data, &category, &variables))
+}
+
+/// Retrieves the game, category and variables for `run` asynchronously and
+/// checks it with [`check`].
+pub async fn validate_async<C>(
+    client: &C,
+    run: &CreateRun<'_>,
+) -> Result<Vec<Problem>, ApiError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    let (category_endpoint, variables_endpoint) = endpoints(&run.category);
+    let CategoryWithGame { category, game } = category_endpoint.query_async(client).await?;
+    let variables: Vec<types::Variable<'static>> = variables_endpoint.query_async(client).await?;
+
+    Ok(check(run, &game.data, &category, &variables))
+}
+
+fn endpoints<'a>(category: &CategoryId<'a>) -> (Category<'a>, CategoryVariables<'a>) {
+    let category_endpoint = Category::builder()
+        .id(category.clone())
+        .embed(CategoryEmbeds::Game)
+        .build()
+        .expect("all required fields are set");
+    let variables_endpoint = CategoryVariables::builder()
+        .id(category.clone())
+        .build()
+        .expect("all required fields are set");
+    (category_endpoint, variables_endpoint)
+}
+
+/// Default tolerance, in seconds, when comparing the times of runs.
+pub const DEFAULT_TOLERANCE: f64 = 0.5;
+
+/// An existing run that is likely the same as a run about to be submitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    /// The existing run.
+    pub run: types::Run<'static>,
+    /// Largest difference between the times of both runs, in seconds.
+    pub difference: f64,
+}
+
+impl Duplicate {
+    /// If the existing run is still waiting for verification.
+    pub fn is_pending(&self) -> bool {
+        matches!(self.run.status, types::Status::New)
+    }
+}
+
+/// Finds existing runs by the same submitter in the same category (and level)
+/// with the same times as `run`, within `tolerance` seconds.
+///
+/// The submitter is the first player of the run, or the authenticated user if
+/// the run has no players. Both verified and pending runs are reported,
+/// rejected runs are not.
+pub fn find_duplicates<C>(
+    client: &C,
+    run: &CreateRun<'_>,
+    tolerance: f64,
+) -> Result<Vec<Duplicate>, ApiError<C::Error>>
+where
+    C: Client,
+{
+    let submitter = match run.players.first() {
+        Some(player) => player.clone(),
+        None => {
+            let user: types::User<'static> = Profile::builder()
+                .build()
+                .expect("all required fields are set")
+                .query(client)?;
+            Player::User { id: user.id }
+        }
+    };
+    let endpoint = duplicates_endpoint(run, &submitter);
+    let mut duplicates = Vec::new();
+    for existing in endpoint.iter::<types::Run<'static>, _>(client) {
+        duplicates.extend(duplicate_of(run, existing?, tolerance));
+    }
+    Ok(duplicates)
+}
+
+/// Finds existing runs by the same submitter in the same category (and level)
+/// with the same times as `run`, within `tolerance` seconds, asynchronously.
+///
+/// See [`find_duplicates`].
+pub async fn find_duplicates_async<C>(
+    client: &C,
+    run: &CreateRun<'_>,
+    tolerance: f64,
+) -> Result<Vec<Duplicate>, ApiError<C::Error>>
+where
+    C: AsyncClient + Sync,
+{
+    let submitter = match run.players.first() {
+        Some(player) => player.clone(),
+        None => {
+            let user: types::User<'static> = Profile::builder()
+                .build()
+                .expect("all required fields are set")
+                .query_async(client)
+                .await?;
+            Player::User { id: user.id }
+        }
+    };
+    let endpoint = duplicates_endpoint(run, &submitter);
+    let existing: Vec<types::Run<'static>> = endpoint.stream(client).try_collect().await?;
+    Ok(existing
+        .into_iter()
+        .filter_map(|existing| duplicate_of(run, existing, tolerance))
+        .collect())
+}
+
+fn duplicates_endpoint<'a>(run: &CreateRun<'a>, submitter: &Player<'a>) -> Runs<'a> {
+    let mut builder = Runs::builder();
+    builder.category(run.category.clone());
+    if let Some(level) = &run.level {
+        builder.level(level.clone());
+    }
+    match submitter {
+        Player::User { id } => builder.user(id.clone()),
+        Player::Guest { name } => builder.guest(name.clone()),
+    };
+    builder.build().expect("all required fields are set")
+}
+
+/// Compares `existing` with `run`, returning a [`Duplicate`] if all the times
+/// set on `run` match.
+fn duplicate_of(
+    run: &CreateRun<'_>,
+    existing: types::Run<'static>,
+    tolerance: f64,
+) -> Option<Duplicate> {
+    if matches!(existing.status, types::Status::Rejected { .. })
+        || existing.category != run.category
+        || existing.level.as_ref().map(ToString::to_string)
+            != run.level.as_ref().map(ToString::to_string)
+    {
+        return None;
+    }
+
+    let times = &existing.times;
+    let pairs = [
+        (run.times.realtime, times.realtime_t),
+        (run.times.realtime_noloads, times.realtime_noloads_t),
+        (run.times.ingame, times.ingame_t),
+    ];
+    let mut difference: Option<f64> = None;
+    for (time, existing_time) in pairs {
+        if let Some(time) = time {
+            let delta = (time - existing_time).abs();
+            if delta > tolerance {
+                return None;
+            }
+            difference = Some(difference.map_or(delta, |max| max.max(delta)));
+        }
+    }
+    difference.map(|difference| Duplicate {
+        run: existing,
+        difference,
+    })
+}
+
+/// Local record of submitted runs by idempotency key.
+///
+/// Used by [`submit`] and [`submit_async`] so that retrying a submission
+/// returns the run created earlier, rather than creating another one.
+pub trait SubmissionStore {
+    /// The run submitted with `key`, if any.
+    fn get(&self, key: &str) -> Option<RunId<'static>>;
+
+    /// Record that `id` was submitted with `key`.
+    fn insert(&mut self, key: &str, id: RunId<'static>) -> io::Result<()>;
+}
+
+/// A [`SubmissionStore`] kept in memory.
+#[derive(Debug, Default, Clone)]
+pub struct SubmissionMemoryStore {
+    runs: HashMap<String, RunId<'static>>,
+}
+
+impl SubmissionMemoryStore {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SubmissionStore for SubmissionMemoryStore {
+    fn get(&self, key: &str) -> Option<RunId<'static>> {
+        self.runs.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: &str, id: RunId<'static>) -> io::Result<()> {
+        self.runs.insert(key.to_owned(), id);
+        Ok(())
+    }
+}
+
+/// A [`SubmissionStore`] persisted to a file, one `key<TAB>run id` per line.
+#[derive(Debug, Clone)]
+pub struct SubmissionFileStore {
+    path: PathBuf,
+    runs: HashMap<String, RunId<'static>>,
+}
+
+impl SubmissionFileStore {
+    /// Open the store at `path`, creating it on the first insert if it does
+    /// not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_owned();
+        let runs = match fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .map(|(key, id)| (key.to_owned(), RunId::new(id.to_owned())))
+                .collect(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self { path, runs })
+    }
+}
+
+impl SubmissionStore for SubmissionFileStore {
+    fn get(&self, key: &str) -> Option<RunId<'static>> {
+        self.runs.get(key).cloned()
+    }
+
+    fn insert(&mut self, key: &str, id: RunId<'static>) -> io::Result<()> {
+        if key.contains(['\t', '\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "idempotency keys cannot contain tabs or line breaks",
+            ));
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{key}\t{id}")?;
+        file.sync_data()?;
+        self.runs.insert(key.to_owned(), id);
+        Ok(())
+    }
+}
+
+/// Errors that occur when submitting a run with [`submit`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SubmitError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The run was created, but could not be recorded in the store.
+    #[error("run `{id}` was created, but could not be recorded: {source}")]
+    Store {
+        /// `ID` of the created run.
+        id: RunId<'static>,
+        /// Source of the error.
+        source: io::Error,
+    },
+}
+
+/// Outcome of [`submit`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Submitted {
+    /// A new run was created.
+    Created(RunId<'static>),
+    /// The run was already submitted with the same key.
+    Existing(RunId<'static>),
+}
+
+impl Submitted {
+    /// `ID` of the run.
+    pub fn id(&self) -> &RunId<'static> {
+        match self {
+            Submitted::Created(id) | Submitted::Existing(id) => id,
+        }
+    }
+}
+
+/// A stable idempotency key derived from the contents of `run`.
+pub fn idempotency_key(run: &CreateRun<'_>) -> String {
+    let value = serde_json::to_value(run).expect("run serializes to JSON");
+    let mut canonical = String::new();
+    write_canonical(&value, &mut canonical);
+    // FNV-1a, which (unlike `DefaultHasher`) is stable across releases.
+    let hash = canonical
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// Write `value` as compact JSON with the keys of every object sorted, so the
+/// output depends neither on the iteration order of the variables nor on
+/// whether serde_json preserves insertion order.
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::from(key.as_str()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Submits `run`, unless a run was already submitted with `key`.
+///
+/// The `ID` of a newly created run is recorded in `store` under `key`.
+pub fn submit<C, S>(
+    client: &C,
+    store: &mut S,
+    key: &str,
+    run: &CreateRun<'_>,
+) -> Result<Submitted, SubmitError<C::Error>>
+where
+    C: Client,
+    S: SubmissionStore,
+{
+    if let Some(id) = store.get(key) {
+        return Ok(Submitted::Existing(id));
+    }
+    let created: types::Run<'static> = run.query(client)?;
+    record(store, key, created.id)
+}
+
+/// Submits `run` asynchronously, unless a run was already submitted with `key`.
+///
+/// See [`submit`].
+pub async fn submit_async<C, S>(
+    client: &C,
+    store: &mut S,
+    key: &str,
+    run: &CreateRun<'_>,
+) -> Result<Submitted, SubmitError<C::Error>>
+where
+    C: AsyncClient + Sync,
+    S: SubmissionStore,
+{
+    if let Some(id) = store.get(key) {
+        return Ok(Submitted::Existing(id));
+    }
+    let created: types::Run<'static> = run.query_async(client).await?;
+    record(store, key, created.id)
+}
+
+fn record<S, E>(store: &mut S, key: &str, id: RunId<'static>) -> Result<Submitted, SubmitError<E>>
+where
+    S: SubmissionStore,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match store.insert(key, id.clone()) {
+        Ok(()) => Ok(Submitted::Created(id)),
+        Err(source) => Err(SubmitError::Store { id, source }),
+    }
+}
+
+/// If `variable` applies to a run in `category` (and `level`).
+fn applies_to(
+    variable: &types::Variable<'_>,
+    category: &CategoryId<'_>,
+    level: Option<&LevelId<'_>>,
+) -> bool {
+    let category_matches = variable
+        .category
+        .as_ref()
+        .is_none_or(|applies_to| applies_to == category);
+    let scope_matches = match (&variable.scope, level) {
+        (Scope::Global, _) => true,
+        (Scope::FullGame, level) => level.is_none(),
+        (Scope::AllLevels, level) => level.is_some(),
+        (Scope::SingleLevel { level: id }, Some(level)) => level.to_string() == *id,
+        (Scope::SingleLevel { .. }, None) => false,
+    };
+    category_matches && scope_matches
+}
+
+fn is_value_of(variable: &types::Variable<'_>, value: &ValueId<'_>) -> bool {
+    variable.values.values.contains_key(value)
+}
+
+fn describe_players(players: &Players) -> String {
+    match players {
+        Players::Exactly(count) => format!("exactly {count}"),
+        Players::UpTo(count) => format!("up to {count}"),
+    }
+}
+
+fn owned_category_id(id: &CategoryId<'_>) -> CategoryId<'static> {
+    CategoryId::new(id.to_string())
+}
+
+fn owned_variable_id(id: &VariableId<'_>) -> VariableId<'static> {
+    VariableId::new(id.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures::{self, game};
+
+    fn category() -> types::Category<'static> {
+        fixtures::category("per-game")
+    }
+
+    fn variables() -> Vec<types::Variable<'static>> {
+        serde_json::from_value(json!([fixtures::variable_json(
+            "ver",
+            json!({ "type": "full-game" }),
+            &[("jp", "JP")]
+        )]))
+        .unwrap()
+    }
+
+    #[test]
+    fn reports_every_problem() {
+        let run = CreateRun::buider()
+            .category("cat")
+            .level("lvl")
+            .platform("n64")
+            .ingame(60.0)
+            .emulated(true)
+            .build()
+            .unwrap();
+
+        let problems = check(&run, &game(), &category(), &variables());
+        assert_eq!(
+            problems,
+            [
+                Problem::TimingMethodNotAllowed(TimingMethod::Ingame),
+                Problem::MissingVideo,
+                Problem::EmulatorNotAllowed,
+                Problem::UnexpectedLevel(CategoryId::new("cat"), LevelId::new("lvl")),
+                Problem::InvalidPlatform(PlatformId::new("n64")),
+            ]
+        );
+    }
+
+    #[test]
+    fn valid_run() {
+        let run = CreateRun::buider()
+            .category("cat")
+            .platform("pc")
+            .realtime(60.0)
+            .emulated(false)
+            .video(url::Url::parse("https://example.com/video").unwrap())
+            .variable("ver", "jp")
+            .build()
+            .unwrap();
+
+        assert_eq!(check(&run, &game(), &category(), &variables()), []);
+    }
+
+    #[test]
+    fn platforms_and_regions_of_the_game() {
+        let run = CreateRun::buider()
+            .category("cat")
+            .platform("n64")
+            .region("jpn")
+            .realtime(60.0)
+            .emulated(false)
+            .video(url::Url::parse("https://example.com/video").unwrap())
+            .variable("ver", "jp")
+            .build()
+            .unwrap();
+
+        let mut game = game();
+        assert_eq!(
+            check(&run, &game, &category(), &variables()),
+            [Problem::InvalidPlatform(PlatformId::new("n64"))]
+        );
+        game.platforms.clear();
+        game.regions.push(RegionId::new("eur"));
+        assert_eq!(
+            check(&run, &game, &category(), &variables()),
+            [Problem::InvalidRegion(RegionId::new("jpn"))]
+        );
+    }
+
+    #[test]
+    fn user_defined_values() {
+        let run = CreateRun::buider()
+            .category("cat")
+            .realtime(60.0)
+            .emulated(false)
+            .video(url::Url::parse("https://example.com/video").unwrap())
+            .user_defined_variable("ver", "1234")
+            .build()
+            .unwrap();
+
+        let mut variables = variables();
+        assert_eq!(
+            check(&run, &game(), &category(), &variables),
+            [Problem::UserDefinedValue {
+                id: VariableId::new("ver"),
+                name: "ver".into(),
+            }]
+        );
+        variables[0].user_defined = true;
+        assert_eq!(check(&run, &game(), &category(), &variables), []);
+    }
+
+    #[test]
+    fn idempotency_key_ignores_variable_order() {
+        let run = |variables: &[(&'static str, &'static str)]| {
+            let mut builder = CreateRun::buider();
+            builder.category("cat").realtime(60.0).emulated(false);
+            for (variable, value) in variables {
+                builder.variable(*variable, *value);
+            }
+            builder.build().unwrap()
+        };
+
+        let key = idempotency_key(&run(&[("a", "1"), ("b", "2"), ("c", "3")]));
+        assert_eq!(
+            key,
+            idempotency_key(&run(&[("c", "3"), ("a", "1"), ("b", "2")]))
+        );
+        assert_ne!(key, idempotency_key(&run(&[("a", "1"), ("b", "2")])));
+    }
+
+    #[test]
+    fn canonical_json() {
+        let value = serde_json::json!({ "b": [{ "d": 1, "c": "x\"y" }], "a": null });
+        let mut canonical = String::new();
+        write_canonical(&value, &mut canonical);
+        assert_eq!(canonical, r#"{"a":null,"b":[{"c":"x\"y","d":1}]}"#);
+    }
+
+    #[test]
+    fn file_store_persists() {
+        let path = std::env::temp_dir().join(format!("speedrun-api-store-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut store = SubmissionFileStore::open(&path).unwrap();
+        assert_eq!(store.get("key"), None);
+        store.insert("key", RunId::new("run")).unwrap();
+        assert!(store.insert("bad\tkey", RunId::new("run")).is_err());
+
+        let store = SubmissionFileStore::open(&path).unwrap();
+        assert_eq!(store.get("key"), Some(RunId::new("run")));
+        fs::remove_file(&path).unwrap();
+    }
+}
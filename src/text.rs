@@ -0,0 +1,57 @@
+//! Text helpers shared by the templates and renderers.
+
+/// Replace the `{name}` placeholders of `template` with `value(name)`.
+///
+/// The template is scanned once, so substituted values are never searched for
+/// placeholders. Placeholders without a value are kept as written.
+pub(crate) fn substitute<F>(template: &str, mut value: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let substituted = after
+            .find('}')
+            .and_then(|end| Some((end, value(&after[..end])?)));
+        match substituted {
+            Some((end, value)) => {
+                out.push_str(&value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Escape `value` for HTML text and attribute values.
+pub(crate) fn escape_html(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn substitute_once() {
+        let value = |name: &str| match name {
+            "a" => Some("{b}".to_owned()),
+            "b" => Some("B".to_owned()),
+            _ => None,
+        };
+        assert_eq!(substitute("{a} {b} {c} {{b}", value), "{b} B {c} {B");
+    }
+}
@@ -5,6 +5,8 @@ mod category_impls;
 mod common;
 mod developer_impls;
 mod developers;
+mod embed_impls;
+mod embeds;
 mod engine_impls;
 mod engines;
 mod game_impls;
@@ -19,6 +21,7 @@ mod leaderboard_impls;
 mod leaderboards;
 mod levels;
 mod levels_impls;
+mod link_impls;
 mod notifications;
 mod platform_impls;
 mod platforms;
@@ -39,19 +42,25 @@ mod variables;
 // TODO: Deserialize dates to chrono types
 
 pub use category::{Category, CategoryType, Players};
-pub use common::{Asset, Assets, Link, ModeratorRole, Names, Pagination, TimingMethod, Root};
+pub use common::{
+    Asset, Assets, Link, LinkRel, ModeratorRole, Names, Pagination, Root, TimingMethod,
+};
 pub use developers::Developer;
+pub use embeds::{Embedded, EmbeddedPlayer, Embeds};
 pub use engines::Engine;
 pub use games::{Game, Ruleset};
 pub use gametypes::GameType;
 pub use genres::Genre;
 pub use guests::Guest;
+pub use leaderboard_impls::LeaderboardKindError;
 pub use leaderboards::{Leaderboard, RankedRun};
 pub use levels::Level;
+pub use link_impls::Links;
+pub use notifications::{Item, Notification, ReadStatus};
 pub use platforms::Platform;
 pub use publishers::Publisher;
 pub use regions::Region;
 pub use runs::{Player, Run, Status, System, Times, VideoLink, Videos};
 pub use series::Series;
-pub use users::{BasicLink, Color, Location, NameStyle, Place, User, UserRole};
+pub use users::{BasicLink, Color, Location, NameStyle, Place, Rgb, Theme, User, UserRole};
 pub use variables::{Flags, Scope, Value, Values, Variable};
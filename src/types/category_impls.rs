@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::categories::CategoryId;
+use crate::api::{self, categories::CategoryId, links::LinkEndpoint};
 
-use super::Category;
+use super::{link_impls::find_link, Category, LinkRel};
 
 impl<'a> From<Category<'a>> for CategoryId<'a> {
     fn from(value: Category<'a>) -> Self {
@@ -10,6 +10,48 @@ impl<'a> From<Category<'a>> for CategoryId<'a> {
     }
 }
 
+/// Endpoint that retrieves the category again.
+impl<'a> From<&Category<'a>> for api::categories::Category<'a> {
+    fn from(value: &Category<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Category<'_> {
+    /// Endpoint for this category.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for the game this category belongs to.
+    pub fn game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Game)
+    }
+
+    /// Endpoint for variables of this category.
+    pub fn variables_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Variables)
+    }
+
+    /// Endpoint for records of this category.
+    pub fn records_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Records)
+    }
+
+    /// Endpoint for runs in this category.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+
+    /// Endpoint for the leaderboard of this category.
+    pub fn leaderboard_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Leaderboard)
+    }
+}
+
 impl Display for Category<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -3,10 +3,47 @@ use serde::{Deserialize, Serialize};
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Link {
-    pub rel: String,
+    pub rel: LinkRel,
     pub uri: String,
 }
 
+/// Relation of a [`Link`] to the resource it is included in.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub enum LinkRel {
+    /// The resource itself (`self`).
+    #[default]
+    SelfLink,
+    Game,
+    Category,
+    Level,
+    Platform,
+    Region,
+    Examiner,
+    User,
+    Guest,
+    Runs,
+    Games,
+    Levels,
+    Categories,
+    Variables,
+    Records,
+    Leaderboard,
+    Series,
+    BaseGame,
+    DerivedGames,
+    Romhacks,
+    PersonalBests,
+    /// Splits for a run (`splits.io`).
+    SplitsIo,
+    /// Next page of a paginated result.
+    Next,
+    /// Previous page of a paginated result.
+    Prev,
+    /// Any relation not documented by the API.
+    Other(String),
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Pagination {
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::developers::DeveloperId;
+use crate::api::{self, developers::DeveloperId, links::LinkEndpoint};
 
-use super::Developer;
+use super::{link_impls::find_link, Developer, LinkRel};
 
 impl<'a> From<Developer<'a>> for DeveloperId<'a> {
     fn from(value: Developer<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<Developer<'a>> for DeveloperId<'a> {
     }
 }
 
+/// Endpoint that retrieves the developer again.
+impl<'a> From<&Developer<'a>> for api::developers::Developer<'a> {
+    fn from(value: &Developer<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Developer<'_> {
+    /// Endpoint for this developer.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games developed by this developer.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for Developer<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
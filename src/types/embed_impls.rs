@@ -0,0 +1,271 @@
+use std::ops::Deref;
+
+use serde::{
+    de::{DeserializeOwned, Error as _},
+    Deserialize, Deserializer,
+};
+use serde_json::{Map, Value};
+
+use crate::api::{
+    categories::CategoryId, games::GameId, levels::LevelId, platforms::PlatformId,
+    regions::RegionId, users::UserId, variables::VariableId,
+};
+
+use super::{
+    Category, Embedded, EmbeddedPlayer, Embeds, Game, Level, LinkRel, Links, Platform, Player,
+    Region, User, Variable,
+};
+
+impl<T> Embedded<T> {
+    /// Wrap `data` without any embedded resources.
+    pub fn new(data: T) -> Self {
+        Self {
+            data,
+            embeds: Embeds::default(),
+        }
+    }
+}
+
+impl<T> From<T> for Embedded<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for Embedded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Embedded<T>
+where
+    T: DeserializeOwned,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut value = Value::deserialize(deserializer)?;
+        let embeds = match value.as_object_mut() {
+            Some(fields) => Embeds::take(fields).map_err(D::Error::custom)?,
+            None => Embeds::default(),
+        };
+        let data = serde_json::from_value(value).map_err(D::Error::custom)?;
+        Ok(Self { data, embeds })
+    }
+}
+
+impl Embeds<'_> {
+    /// The game with `id`.
+    pub fn game(&self, id: &GameId<'_>) -> Option<&Game<'_>> {
+        self.game.as_ref().filter(|game| game.id == *id)
+    }
+
+    /// The category with `id`.
+    pub fn category(&self, id: &CategoryId<'_>) -> Option<&Category<'_>> {
+        self.category
+            .iter()
+            .chain(&self.categories)
+            .find(|category| category.id == *id)
+    }
+
+    /// The level with `id`.
+    pub fn level(&self, id: &LevelId<'_>) -> Option<&Level<'_>> {
+        self.level
+            .iter()
+            .chain(&self.levels)
+            .find(|level| level.id == *id)
+    }
+
+    /// The platform with `id`.
+    pub fn platform(&self, id: &PlatformId<'_>) -> Option<&Platform<'_>> {
+        self.platform
+            .iter()
+            .chain(&self.platforms)
+            .find(|platform| platform.id == *id)
+    }
+
+    /// The region with `id`.
+    pub fn region(&self, id: &RegionId<'_>) -> Option<&Region<'_>> {
+        self.region
+            .iter()
+            .chain(&self.regions)
+            .find(|region| region.id == *id)
+    }
+
+    /// The variable with `id`.
+    pub fn variable(&self, id: &VariableId<'_>) -> Option<&Variable<'_>> {
+        self.variables.iter().find(|variable| variable.id == *id)
+    }
+
+    /// The user with `id`.
+    pub fn user(&self, id: &UserId<'_>) -> Option<&User<'_>> {
+        self.players.iter().find_map(|player| match player {
+            EmbeddedPlayer::User(user) if user.id == *id => Some(user.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Display name of `player`: the name of an embedded user, the `ID` of
+    /// other users, or the name of a guest.
+    pub fn player_name(&self, player: &Player<'_>) -> String {
+        match player {
+            Player::User { id, .. } => self
+                .user(id)
+                .map(|user| user.names.international.clone())
+                .unwrap_or_else(|| id.to_string()),
+            Player::Guest { name, .. } => name.clone(),
+        }
+    }
+}
+
+impl Embeds<'static> {
+    /// Move the embedded resources out of `fields`, putting back the
+    /// references found in responses without embeds.
+    fn take(fields: &mut Map<String, Value>) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            game: take_one(fields, "game")?,
+            category: take_one(fields, "category")?,
+            level: take_one(fields, "level")?,
+            platform: take_one(fields, "platform")?,
+            region: take_one(fields, "region")?,
+            players: take_players(fields)?,
+            categories: take_many(fields, "categories")?,
+            levels: take_many(fields, "levels")?,
+            variables: take_many(fields, "variables")?,
+            platforms: take_many(fields, "platforms")?,
+            regions: take_many(fields, "regions")?,
+        })
+    }
+}
+
+impl EmbeddedPlayer<'_> {
+    /// Display name of the player.
+    pub fn name(&self) -> &str {
+        match self {
+            EmbeddedPlayer::User(user) => &user.names.international,
+            EmbeddedPlayer::Guest(guest) => &guest.name,
+        }
+    }
+
+    /// The player as referenced from a run without embeds.
+    fn reference(&self) -> Value {
+        match self {
+            EmbeddedPlayer::User(user) => serde_json::json!({
+                "rel": "user",
+                "id": user.id.to_string(),
+                "uri": self_uri(user.links.find(LinkRel::SelfLink)),
+            }),
+            EmbeddedPlayer::Guest(guest) => serde_json::json!({
+                "rel": "guest",
+                "name": guest.name,
+                "uri": self_uri(guest.links.find(LinkRel::SelfLink)),
+            }),
+        }
+    }
+}
+
+/// The data of `field` if it is an embed (`{"data": ...}`).
+fn embed(fields: &Map<String, Value>, field: &str) -> Option<Value> {
+    match fields.get(field)? {
+        Value::Object(embed) => embed.get("data").cloned(),
+        _ => None,
+    }
+}
+
+/// Take a single embedded resource, replacing it by its `ID`. Empty embeds
+/// (e.g. the level of a full-game run) are replaced by `null`.
+fn take_one<T>(fields: &mut Map<String, Value>, field: &str) -> Result<Option<T>, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    let Some(data) = embed(fields, field) else {
+        return Ok(None);
+    };
+    if !data.is_object() {
+        fields.insert(field.to_owned(), Value::Null);
+        return Ok(None);
+    }
+    fields.insert(field.to_owned(), data["id"].clone());
+    serde_json::from_value(data).map(Some)
+}
+
+/// Take a list of embedded resources, replacing it by their `ID`s.
+fn take_many<T>(fields: &mut Map<String, Value>, field: &str) -> Result<Vec<T>, serde_json::Error>
+where
+    T: DeserializeOwned,
+{
+    let Some(data) = embed(fields, field) else {
+        return Ok(Vec::new());
+    };
+    let ids = data
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|item| item["id"].clone())
+        .collect();
+    fields.insert(field.to_owned(), Value::Array(ids));
+    serde_json::from_value(data)
+}
+
+/// Take the embedded players, replacing them by player references.
+fn take_players(
+    fields: &mut Map<String, Value>,
+) -> Result<Vec<EmbeddedPlayer<'static>>, serde_json::Error> {
+    let Some(data) = embed(fields, "players") else {
+        return Ok(Vec::new());
+    };
+    let players: Vec<EmbeddedPlayer<'static>> = serde_json::from_value(data)?;
+    fields.insert(
+        "players".to_owned(),
+        players.iter().map(EmbeddedPlayer::reference).collect(),
+    );
+    Ok(players)
+}
+
+fn self_uri(link: Option<&super::Link>) -> &str {
+    link.map(|link| link.uri.as_str()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::types::Leaderboard;
+
+    #[test]
+    fn leaderboard_with_embeds() {
+        let leaderboard: Embedded<Leaderboard<'static>> = serde_json::from_value(json!({
+            "weblink": "https://www.speedrun.com/g#c",
+            "game": "g",
+            "category": "c",
+            "level": { "data": [] },
+            "platform": null,
+            "video-only": false,
+            "values": {},
+            "runs": [],
+            "links": [],
+            "players": { "data": [
+                { "rel": "guest", "name": "someone", "links": [] }
+            ] },
+            "platforms": { "data": [
+                { "id": "p", "name": "PC", "released": 1981, "links": [] }
+            ] }
+        }))
+        .unwrap();
+
+        assert_eq!(leaderboard.level, None);
+        assert_eq!(leaderboard.embeds.players[0].name(), "someone");
+        assert_eq!(
+            leaderboard
+                .embeds
+                .platform(&PlatformId::new("p"))
+                .map(|platform| platform.name.as_str()),
+            Some("PC")
+        );
+    }
+}
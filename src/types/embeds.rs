@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use super::{Category, Game, Guest, Level, Platform, Region, User, Variable};
+
+/// A resource together with the resources embedded in its response (see the
+/// `embed` option of the endpoints).
+///
+/// Embedded resources are moved into [`Embeds`] and replaced by their `ID`s,
+/// so `data` deserializes the same with or without embeds.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Embedded<T> {
+    pub data: T,
+    pub embeds: Embeds<'static>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Embeds<'a> {
+    pub game: Option<Game<'a>>,
+    pub category: Option<Category<'a>>,
+    pub level: Option<Level<'a>>,
+    pub platform: Option<Platform<'a>>,
+    pub region: Option<Region<'a>>,
+    pub players: Vec<EmbeddedPlayer<'a>>,
+    pub categories: Vec<Category<'a>>,
+    pub levels: Vec<Level<'a>>,
+    pub variables: Vec<Variable<'a>>,
+    pub platforms: Vec<Platform<'a>>,
+    pub regions: Vec<Region<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(tag = "rel")]
+pub enum EmbeddedPlayer<'a> {
+    User(Box<User<'a>>),
+    Guest(Guest),
+}
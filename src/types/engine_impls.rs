@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::engines::EngineId;
+use crate::api::{self, engines::EngineId, links::LinkEndpoint};
 
-use super::Engine;
+use super::{link_impls::find_link, Engine, LinkRel};
 
 impl<'a> From<Engine<'a>> for EngineId<'a> {
     fn from(value: Engine<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<Engine<'a>> for EngineId<'a> {
     }
 }
 
+/// Endpoint that retrieves the engine again.
+impl<'a> From<&Engine<'a>> for api::engines::Engine<'a> {
+    fn from(value: &Engine<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Engine<'_> {
+    /// Endpoint for this engine.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games using this engine.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for Engine<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::games::GameId;
+use crate::api::{self, games::GameId, links::LinkEndpoint};
 
-use super::Game;
+use super::{link_impls::find_link, Game, LinkRel};
 
 impl<'a> From<Game<'a>> for GameId<'a> {
     fn from(value: Game<'a>) -> Self {
@@ -10,6 +10,73 @@ impl<'a> From<Game<'a>> for GameId<'a> {
     }
 }
 
+/// Endpoint that retrieves the game again.
+impl<'a> From<&Game<'a>> for api::games::Game<'a> {
+    fn from(value: &Game<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Game<'_> {
+    /// Endpoint for this game.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for runs of this game.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+
+    /// Endpoint for levels of this game.
+    pub fn levels_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Levels)
+    }
+
+    /// Endpoint for categories of this game.
+    pub fn categories_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Categories)
+    }
+
+    /// Endpoint for variables of this game.
+    pub fn variables_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Variables)
+    }
+
+    /// Endpoint for records of this game.
+    pub fn records_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Records)
+    }
+
+    /// Endpoint for the series this game belongs to.
+    pub fn series_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Series)
+    }
+
+    /// Endpoint for the base game of this game.
+    pub fn base_game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::BaseGame)
+    }
+
+    /// Endpoint for games derived from this game.
+    pub fn derived_games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::DerivedGames)
+    }
+
+    /// Endpoint for romhacks of this game.
+    pub fn romhacks_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Romhacks)
+    }
+
+    /// Endpoint for the leaderboard of the default category of this game.
+    pub fn leaderboard_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Leaderboard)
+    }
+}
+
 impl Display for Game<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.names.international)
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::gametypes::GameTypeId;
+use crate::api::{self, gametypes::GameTypeId, links::LinkEndpoint};
 
-use super::GameType;
+use super::{link_impls::find_link, GameType, LinkRel};
 
 impl<'a> From<GameType<'a>> for GameTypeId<'a> {
     fn from(value: GameType<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<GameType<'a>> for GameTypeId<'a> {
     }
 }
 
+/// Endpoint that retrieves the game type again.
+impl<'a> From<&GameType<'a>> for api::gametypes::GameType<'a> {
+    fn from(value: &GameType<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl GameType<'_> {
+    /// Endpoint for this game type.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games of this game type.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for GameType<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
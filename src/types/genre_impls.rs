@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::genres::GenreId;
+use crate::api::{self, genres::GenreId, links::LinkEndpoint};
 
-use super::Genre;
+use super::{link_impls::find_link, Genre, LinkRel};
 
 impl<'a> From<Genre<'a>> for GenreId<'a> {
     fn from(value: Genre<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<Genre<'a>> for GenreId<'a> {
     }
 }
 
+/// Endpoint that retrieves the genre again.
+impl<'a> From<&Genre<'a>> for api::genres::Genre<'a> {
+    fn from(value: &Genre<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Genre<'_> {
+    /// Endpoint for this genre.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games in this genre.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for Genre<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
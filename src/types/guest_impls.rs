@@ -1,6 +1,38 @@
 use std::fmt::Display;
 
-use super::Guest;
+use crate::api::{self, links::LinkEndpoint, runs::Player};
+
+use super::{link_impls::find_link, Guest, LinkRel};
+
+impl From<&Guest> for Player<'static> {
+    fn from(value: &Guest) -> Self {
+        Player::Guest {
+            name: value.name.clone().into(),
+        }
+    }
+}
+
+/// Endpoint that retrieves the guest again.
+impl From<&Guest> for api::guests::Guest<'static> {
+    fn from(value: &Guest) -> Self {
+        Self::builder()
+            .name(value.name.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Guest {
+    /// Endpoint for this guest.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for runs done by this guest.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+}
 
 impl Display for Guest {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
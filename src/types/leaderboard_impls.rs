@@ -1,6 +1,51 @@
-use crate::api::{categories::CategoryId, games::GameId};
+use std::collections::HashMap;
 
-use super::Leaderboard;
+use thiserror::Error;
+
+use crate::api::{
+    categories::CategoryId,
+    games::GameId,
+    leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard},
+    links::LinkEndpoint,
+    variables::{ValueId, VariableId},
+};
+
+use super::{link_impls::find_link, Leaderboard, LinkRel};
+
+/// Error converting a [`Leaderboard`] into the endpoint for the other kind of
+/// leaderboard.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum LeaderboardKindError {
+    /// The leaderboard belongs to a level.
+    #[error("leaderboard is an individual-level leaderboard")]
+    IndividualLevel,
+    /// The leaderboard does not belong to a level.
+    #[error("leaderboard is a full-game leaderboard")]
+    FullGame,
+}
+
+/// Set the filters of the leaderboard `$value` on `$builder`, a builder of
+/// either leaderboard endpoint (both have the same filter setters).
+macro_rules! set_filters {
+    ($builder:ident, $value:ident) => {
+        $builder
+            .video_only($value.video_only)
+            .variables(values(&$value.values));
+        if let Some(platform) = &$value.platform {
+            $builder.platform(platform.clone());
+        }
+        if let Some(region) = &$value.region {
+            $builder.region(region.clone());
+        }
+        if let Some(emulators) = $value.emulators {
+            $builder.emulators(emulators);
+        }
+        if let Some(timing) = &$value.timing {
+            $builder.timing(timing.clone());
+        }
+    };
+}
 
 impl<'a> From<Leaderboard<'a>> for CategoryId<'a> {
     fn from(value: Leaderboard<'a>) -> Self {
@@ -13,3 +58,62 @@ impl<'a> From<Leaderboard<'a>> for GameId<'a> {
         value.game
     }
 }
+
+/// Endpoint that retrieves the leaderboard again, with the same filters.
+impl<'a> TryFrom<&Leaderboard<'a>> for FullGameLeaderboard<'a> {
+    type Error = LeaderboardKindError;
+
+    fn try_from(value: &Leaderboard<'a>) -> Result<Self, Self::Error> {
+        if value.level.is_some() {
+            return Err(LeaderboardKindError::IndividualLevel);
+        }
+        let mut builder = FullGameLeaderboard::builder();
+        builder
+            .game(value.game.clone())
+            .category(value.category.clone());
+        set_filters!(builder, value);
+        Ok(builder.build().expect("all required fields are set"))
+    }
+}
+
+/// Endpoint that retrieves the leaderboard again, with the same filters.
+impl<'a> TryFrom<&Leaderboard<'a>> for IndividualLevelLeaderboard<'a> {
+    type Error = LeaderboardKindError;
+
+    fn try_from(value: &Leaderboard<'a>) -> Result<Self, Self::Error> {
+        let Some(level) = &value.level else {
+            return Err(LeaderboardKindError::FullGame);
+        };
+        let mut builder = IndividualLevelLeaderboard::builder();
+        builder
+            .game(value.game.clone())
+            .level(level.clone())
+            .category(value.category.clone());
+        set_filters!(builder, value);
+        Ok(builder.build().expect("all required fields are set"))
+    }
+}
+
+impl Leaderboard<'_> {
+    /// Endpoint for the game of this leaderboard.
+    pub fn game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Game)
+    }
+
+    /// Endpoint for the category of this leaderboard.
+    pub fn category_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Category)
+    }
+
+    /// Endpoint for the level of this leaderboard.
+    pub fn level_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Level)
+    }
+}
+
+fn values<'a>(values: &HashMap<VariableId<'a>, String>) -> Vec<(VariableId<'a>, ValueId<'a>)> {
+    values
+        .iter()
+        .map(|(var, val)| (var.clone(), ValueId::new(val.clone())))
+        .collect()
+}
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::levels::LevelId;
+use crate::api::{self, levels::LevelId, links::LinkEndpoint};
 
-use super::Level;
+use super::{link_impls::find_link, Level, LinkRel};
 
 impl<'a> From<Level<'a>> for LevelId<'a> {
     fn from(value: Level<'a>) -> Self {
@@ -10,6 +10,48 @@ impl<'a> From<Level<'a>> for LevelId<'a> {
     }
 }
 
+/// Endpoint that retrieves the level again.
+impl<'a> From<&Level<'a>> for api::levels::Level<'a> {
+    fn from(value: &Level<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Level<'_> {
+    /// Endpoint for this level.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for the game this level belongs to.
+    pub fn game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Game)
+    }
+
+    /// Endpoint for categories applicable to this level.
+    pub fn categories_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Categories)
+    }
+
+    /// Endpoint for variables of this level.
+    pub fn variables_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Variables)
+    }
+
+    /// Endpoint for records of this level.
+    pub fn records_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Records)
+    }
+
+    /// Endpoint for runs of this level.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+}
+
 impl Display for Level<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -0,0 +1,228 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::api::links::LinkEndpoint;
+
+use super::{
+    Category, Developer, Engine, Game, GameType, Genre, Guest, Leaderboard, Level, Link, LinkRel,
+    Platform, Publisher, Region, Run, Series, User, Variable,
+};
+
+/// Lookup of hypermedia links by their relation.
+pub trait Links {
+    /// All links of this resource.
+    fn links(&self) -> &[Link];
+
+    /// Find the first link with the relation `rel`.
+    fn find(&self, rel: LinkRel) -> Option<&Link> {
+        self.links().iter().find(|link| link.rel == rel)
+    }
+
+    /// Find all links with the relation `rel`.
+    fn find_all(&self, rel: LinkRel) -> Vec<&Link> {
+        self.links().iter().filter(|link| link.rel == rel).collect()
+    }
+}
+
+impl Link {
+    /// Create an endpoint that retrieves the linked resource.
+    pub fn endpoint(&self) -> LinkEndpoint<'_> {
+        self.into()
+    }
+}
+
+impl LinkRel {
+    /// The relation as it appears in API responses.
+    pub fn as_str(&self) -> &str {
+        match self {
+            LinkRel::SelfLink => "self",
+            LinkRel::Game => "game",
+            LinkRel::Category => "category",
+            LinkRel::Level => "level",
+            LinkRel::Platform => "platform",
+            LinkRel::Region => "region",
+            LinkRel::Examiner => "examiner",
+            LinkRel::User => "user",
+            LinkRel::Guest => "guest",
+            LinkRel::Runs => "runs",
+            LinkRel::Games => "games",
+            LinkRel::Levels => "levels",
+            LinkRel::Categories => "categories",
+            LinkRel::Variables => "variables",
+            LinkRel::Records => "records",
+            LinkRel::Leaderboard => "leaderboard",
+            LinkRel::Series => "series",
+            LinkRel::BaseGame => "base-game",
+            LinkRel::DerivedGames => "derived-games",
+            LinkRel::Romhacks => "romhacks",
+            LinkRel::PersonalBests => "personal-bests",
+            LinkRel::SplitsIo => "splits.io",
+            LinkRel::Next => "next",
+            LinkRel::Prev => "prev",
+            LinkRel::Other(rel) => rel,
+        }
+    }
+}
+
+impl From<&str> for LinkRel {
+    fn from(value: &str) -> Self {
+        match value {
+            "self" => LinkRel::SelfLink,
+            "game" => LinkRel::Game,
+            "category" => LinkRel::Category,
+            "level" => LinkRel::Level,
+            "platform" => LinkRel::Platform,
+            "region" => LinkRel::Region,
+            "examiner" => LinkRel::Examiner,
+            "user" => LinkRel::User,
+            "guest" => LinkRel::Guest,
+            "runs" => LinkRel::Runs,
+            "games" => LinkRel::Games,
+            "levels" => LinkRel::Levels,
+            "categories" => LinkRel::Categories,
+            "variables" => LinkRel::Variables,
+            "records" => LinkRel::Records,
+            "leaderboard" => LinkRel::Leaderboard,
+            "series" => LinkRel::Series,
+            "base-game" => LinkRel::BaseGame,
+            "derived-games" => LinkRel::DerivedGames,
+            "romhacks" => LinkRel::Romhacks,
+            "personal-bests" => LinkRel::PersonalBests,
+            "splits.io" => LinkRel::SplitsIo,
+            "next" => LinkRel::Next,
+            "prev" => LinkRel::Prev,
+            other => LinkRel::Other(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for LinkRel {
+    fn from(value: String) -> Self {
+        value.as_str().into()
+    }
+}
+
+impl FromStr for LinkRel {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(s.into())
+    }
+}
+
+impl Display for LinkRel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl Links for [Link] {
+    fn links(&self) -> &[Link] {
+        self
+    }
+}
+
+impl Links for Option<Vec<Link>> {
+    fn links(&self) -> &[Link] {
+        self.as_deref().unwrap_or_default()
+    }
+}
+
+impl Links for Run<'_> {
+    fn links(&self) -> &[Link] {
+        self.links.links()
+    }
+}
+
+impl Links for Guest {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Category<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Developer<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Engine<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Game<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for GameType<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Genre<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Leaderboard<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Level<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Platform<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Publisher<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Region<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Series<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for User<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+impl Links for Variable<'_> {
+    fn links(&self) -> &[Link] {
+        &self.links
+    }
+}
+
+pub(crate) fn find_link(links: &[Link], rel: LinkRel) -> Option<LinkEndpoint<'_>> {
+    links.find(rel).map(Into::into)
+}
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::platforms::PlatformId;
+use crate::api::{self, links::LinkEndpoint, platforms::PlatformId};
 
-use super::Platform;
+use super::{link_impls::find_link, LinkRel, Platform};
 
 impl<'a> From<Platform<'a>> for PlatformId<'a> {
     fn from(value: Platform<'a>) -> Self {
@@ -10,6 +10,33 @@ impl<'a> From<Platform<'a>> for PlatformId<'a> {
     }
 }
 
+/// Endpoint that retrieves the platform again.
+impl<'a> From<&Platform<'a>> for api::platforms::Platform<'a> {
+    fn from(value: &Platform<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Platform<'_> {
+    /// Endpoint for this platform.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games available on this platform.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+
+    /// Endpoint for runs done on this platform.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+}
+
 impl Display for Platform<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::publishers::PublisherId;
+use crate::api::{self, links::LinkEndpoint, publishers::PublisherId};
 
-use super::Publisher;
+use super::{link_impls::find_link, LinkRel, Publisher};
 
 impl<'a> From<Publisher<'a>> for PublisherId<'a> {
     fn from(value: Publisher<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<Publisher<'a>> for PublisherId<'a> {
     }
 }
 
+/// Endpoint that retrieves the publisher again.
+impl<'a> From<&Publisher<'a>> for api::publishers::Publisher<'a> {
+    fn from(value: &Publisher<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Publisher<'_> {
+    /// Endpoint for this publisher.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games published by this publisher.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for Publisher<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
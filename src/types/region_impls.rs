@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::regions::RegionId;
+use crate::api::{self, links::LinkEndpoint, regions::RegionId};
 
-use super::Region;
+use super::{link_impls::find_link, LinkRel, Region};
 
 impl<'a> From<Region<'a>> for RegionId<'a> {
     fn from(value: Region<'a>) -> Self {
@@ -10,6 +10,33 @@ impl<'a> From<Region<'a>> for RegionId<'a> {
     }
 }
 
+/// Endpoint that retrieves the region again.
+impl<'a> From<&Region<'a>> for api::regions::Region<'a> {
+    fn from(value: &Region<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Region<'_> {
+    /// Endpoint for this region.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games available in this region.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+
+    /// Endpoint for runs done in this region.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+}
+
 impl Display for Region<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -1,6 +1,16 @@
-use crate::api::{categories::CategoryId, games::GameId, runs::RunId};
+use crate::api::{
+    self,
+    categories::CategoryId,
+    games::GameId,
+    links::LinkEndpoint,
+    runs::{CreateRun, CreateRunBuilder, RunId, SplitsIo, ValueType},
+    variables::ValueId,
+};
 
-use super::Run;
+use super::{
+    link_impls::{find_link, Links},
+    LinkRel, Player, Run, Variable,
+};
 
 impl<'a> From<Run<'a>> for RunId<'a> {
     fn from(value: Run<'a>) -> Self {
@@ -19,3 +29,193 @@ impl<'a> From<Run<'a>> for CategoryId<'a> {
         value.category
     }
 }
+
+impl Run<'_> {
+    /// Endpoint for this run.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::SelfLink)
+    }
+
+    /// Endpoint for the game this run belongs to.
+    pub fn game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Game)
+    }
+
+    /// Endpoint for the category of this run.
+    pub fn category_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Category)
+    }
+
+    /// Endpoint for the level of this run.
+    pub fn level_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Level)
+    }
+
+    /// Endpoint for the platform this run was done on.
+    pub fn platform_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Platform)
+    }
+
+    /// Endpoint for the region this run was done in.
+    pub fn region_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Region)
+    }
+
+    /// Endpoint for the user that examined this run.
+    pub fn examiner_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(self.links.links(), LinkRel::Examiner)
+    }
+}
+
+impl<'a> From<Player<'a>> for api::runs::Player<'a> {
+    fn from(value: Player<'a>) -> Self {
+        match value {
+            Player::User { id, .. } => Self::User { id },
+            Player::Guest { name, .. } => Self::Guest { name: name.into() },
+        }
+    }
+}
+
+impl<'a> From<&Player<'a>> for api::runs::Player<'a> {
+    fn from(value: &Player<'a>) -> Self {
+        value.clone().into()
+    }
+}
+
+/// Endpoint that retrieves the run again.
+impl<'a> From<&Run<'a>> for api::runs::Run<'a> {
+    fn from(value: &Run<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl<'a> Run<'a> {
+    /// Builder for submitting the run again, e.g. to migrate it to another
+    /// category.
+    ///
+    /// The status, submission date and examiner are not carried over. Values
+    /// are submitted as [`Variable::run_value`] of their variable in
+    /// `variables`, or as pre-defined values if the variable is not listed.
+    pub fn resubmit(&self, variables: &[Variable<'_>]) -> CreateRunBuilder<'a> {
+        let mut builder = CreateRun::buider();
+        builder
+            .category(self.category.clone())
+            .emulated(self.system.emulated)
+            .players(self.players.iter().map(api::runs::Player::from));
+        if let Some(level) = &self.level {
+            builder.level(level.clone());
+        }
+        if let Some(date) = &self.date {
+            builder.date(date.clone());
+        }
+        if let Some(region) = &self.system.region {
+            builder.region(region.clone());
+        }
+        if let Some(platform) = &self.system.platform {
+            builder.platform(platform.clone());
+        }
+        if self.times.realtime.is_some() {
+            builder.realtime(self.times.realtime_t);
+        }
+        if self.times.realtime_noloads.is_some() {
+            builder.realtime_noloads(self.times.realtime_noloads_t);
+        }
+        if self.times.ingame.is_some() {
+            builder.ingame(self.times.ingame_t);
+        }
+        if let Some(videos) = &self.videos {
+            if let Some(video) = videos
+                .links
+                .iter()
+                .find_map(|link| url::Url::parse(&link.uri).ok())
+            {
+                builder.video(video);
+            }
+        }
+        if let Some(comment) = &self.comment {
+            builder.comment(comment.clone());
+        }
+        if let Some(splits) = self
+            .splits
+            .as_ref()
+            .and_then(|link| url::Url::parse(&link.uri).ok())
+        {
+            builder.splitsio(SplitsIo::Url(splits));
+        }
+        for (id, value) in &self.values {
+            let value = match variables.iter().find(|variable| variable.id == *id) {
+                Some(variable) => variable.run_value(value),
+                None => ValueType::PreDefined {
+                    value: ValueId::new(value.clone()),
+                },
+            };
+            builder.variable_value(id.clone(), value);
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn resubmit_run() {
+        let run: Run<'static> = serde_json::from_value(json!({
+            "id": "abc",
+            "weblink": "https://www.speedrun.com/run/abc",
+            "game": "g",
+            "category": "c",
+            "videos": { "links": [{ "uri": "https://youtu.be/xyz" }] },
+            "status": { "status": "new" },
+            "players": [{ "rel": "guest", "name": "someone", "uri": "" }],
+            "date": "2024-01-02",
+            "times": {
+                "primary": "PT1M", "primary_t": 60.0,
+                "realtime": "PT1M", "realtime_t": 60.0,
+                "realtime_noloads": null, "realtime_noloads_t": 0.0,
+                "ingame": null, "ingame_t": 0.0
+            },
+            "system": { "platform": "p", "emulated": false, "region": null },
+            "values": { "var": "val", "seed": "1234" }
+        }))
+        .unwrap();
+        let mut variables: Vec<Variable<'static>> = serde_json::from_value(json!([
+            fixtures::variable_json("var", json!({ "type": "global" }), &[("val", "Val")]),
+            fixtures::variable_json("seed", json!({ "type": "global" }), &[]),
+        ]))
+        .unwrap();
+        for variable in &mut variables {
+            variable.user_defined = true;
+        }
+
+        let create = run.resubmit(&variables).build().unwrap();
+        assert_eq!(
+            serde_json::to_value(&create).unwrap(),
+            json!({
+                "category": "c",
+                "level": null,
+                "date": "2024-01-02",
+                "region": null,
+                "platform": "p",
+                "verified": null,
+                "times": { "realtime": 60.0, "realtime_noloads": null, "ingame": null },
+                "players": [{ "rel": "guest", "name": "someone" }],
+                "emulated": false,
+                "video": "https://youtu.be/xyz",
+                "comment": null,
+                "splitsio": null,
+                "variables": {
+                    "var": { "type": "pre-defined", "value": "val" },
+                    "seed": { "type": "user-defined", "value": "1234" }
+                }
+            })
+        );
+    }
+}
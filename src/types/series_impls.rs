@@ -1,8 +1,8 @@
 use std::fmt::Display;
 
-use crate::api::series::SeriesId;
+use crate::api::{self, links::LinkEndpoint, series::SeriesId};
 
-use super::Series;
+use super::{link_impls::find_link, LinkRel, Series};
 
 impl<'a> From<Series<'a>> for SeriesId<'a> {
     fn from(value: Series<'a>) -> Self {
@@ -10,6 +10,28 @@ impl<'a> From<Series<'a>> for SeriesId<'a> {
     }
 }
 
+/// Endpoint that retrieves the series again.
+impl<'a> From<&Series<'a>> for api::series::Series<'a> {
+    fn from(value: &Series<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl Series<'_> {
+    /// Endpoint for this series.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for games in this series.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+}
+
 impl Display for Series<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.names.international)
@@ -1,8 +1,11 @@
-use std::fmt::Display;
+use std::fmt::{Display, Write};
 
-use crate::api::users::UserId;
+use crate::{
+    api::{self, links::LinkEndpoint, runs::Player, users::UserId},
+    text::escape_html,
+};
 
-use super::User;
+use super::{link_impls::find_link, Color, LinkRel, NameStyle, Rgb, Theme, User};
 
 impl<'a> From<User<'a>> for UserId<'a> {
     fn from(value: User<'a>) -> Self {
@@ -10,8 +13,245 @@ impl<'a> From<User<'a>> for UserId<'a> {
     }
 }
 
+/// Endpoint that retrieves the user again.
+impl<'a> From<&User<'a>> for api::users::User<'a> {
+    fn from(value: &User<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl<'a> From<&User<'a>> for Player<'a> {
+    fn from(value: &User<'a>) -> Self {
+        Player::User {
+            id: value.id.clone(),
+        }
+    }
+}
+
+impl User<'_> {
+    /// Endpoint for this user.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for runs done by this user.
+    pub fn runs_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Runs)
+    }
+
+    /// Endpoint for games moderated by this user.
+    pub fn games_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Games)
+    }
+
+    /// Endpoint for personal bests of this user.
+    pub fn personal_bests_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::PersonalBests)
+    }
+}
+
+impl User<'_> {
+    /// The name of the user in the colors of their [`NameStyle`], as text for
+    /// terminals supporting 24-bit ANSI colors.
+    pub fn ansi_name(&self, theme: Theme) -> String {
+        self.name_style.ansi(&self.names.international, theme)
+    }
+
+    /// The name of the user in the colors of their [`NameStyle`], as an HTML
+    /// `<span>`.
+    pub fn html_name(&self, theme: Theme) -> String {
+        self.name_style.html(&self.names.international, theme)
+    }
+}
+
+impl NameStyle {
+    /// The colors of the style in `theme`: one for solid styles, the start and
+    /// end of gradients. Colors that cannot be parsed are left out.
+    pub fn rgb(&self, theme: Theme) -> Vec<Rgb> {
+        match self {
+            NameStyle::Solid { color } => color.rgb(theme).into_iter().collect(),
+            NameStyle::Gradient {
+                color_from,
+                color_to,
+            } => color_from
+                .rgb(theme)
+                .into_iter()
+                .chain(color_to.rgb(theme))
+                .collect(),
+        }
+    }
+
+    /// Color of the character at `index` of a name with `len` characters, in
+    /// a style with `colors`.
+    fn color_at(colors: &[Rgb], index: usize, len: usize) -> Option<Rgb> {
+        match colors {
+            [color] => Some(*color),
+            [from, to] => {
+                let t = if len > 1 {
+                    index as f64 / (len - 1) as f64
+                } else {
+                    0.0
+                };
+                Some(from.mix(*to, t))
+            }
+            _ => None,
+        }
+    }
+
+    /// `name` in the colors of the style, as text for terminals supporting
+    /// 24-bit ANSI colors. Gradients color each character. `name` is returned
+    /// unchanged if the colors cannot be parsed.
+    pub fn ansi(&self, name: &str, theme: Theme) -> String {
+        let colors = self.rgb(theme);
+        let len = name.chars().count();
+        let mut styled = String::new();
+        let mut previous = None;
+        for (index, c) in name.chars().enumerate() {
+            let Some(color) = Self::color_at(&colors, index, len) else {
+                return name.to_owned();
+            };
+            if previous != Some(color) {
+                let _ = write!(styled, "\x1b[38;2;{};{};{}m", color.r, color.g, color.b);
+                previous = Some(color);
+            }
+            styled.push(c);
+        }
+        if previous.is_some() {
+            styled.push_str("\x1b[0m");
+        }
+        styled
+    }
+
+    /// `name` in the colors of the style, as an HTML `<span>`. Gradients use a
+    /// CSS `linear-gradient` clipped to the text. The name is escaped.
+    pub fn html(&self, name: &str, theme: Theme) -> String {
+        let name = escape_html(name);
+        match self.rgb(theme).as_slice() {
+            [color] => format!("<span style=\"color: {color}\">{name}</span>"),
+            [from, to] => format!(
+                "<span style=\"color: {from}; background: linear-gradient(90deg, {from}, {to}); \
+                 -webkit-background-clip: text; background-clip: text; \
+                 -webkit-text-fill-color: transparent\">{name}</span>"
+            ),
+            _ => format!("<span>{name}</span>"),
+        }
+    }
+}
+
+impl Color {
+    /// The hex color code for `theme`.
+    pub fn get(&self, theme: Theme) -> &str {
+        match theme {
+            Theme::Light => &self.light,
+            Theme::Dark => &self.dark,
+        }
+    }
+
+    /// The color for `theme`, `None` if it cannot be parsed.
+    pub fn rgb(&self, theme: Theme) -> Option<Rgb> {
+        Rgb::parse(self.get(theme))
+    }
+}
+
+impl Rgb {
+    /// Parse a hex color code (`#rrggbb` or `#rgb`, the `#` is optional).
+    pub fn parse(code: &str) -> Option<Self> {
+        let hex = code.trim().trim_start_matches('#');
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+        let component = |digits: &str| u8::from_str_radix(digits, 16).ok();
+        match hex.len() {
+            6 => Some(Self {
+                r: component(&hex[0..2])?,
+                g: component(&hex[2..4])?,
+                b: component(&hex[4..6])?,
+            }),
+            3 => Some(Self {
+                r: component(&hex[0..1])? * 0x11,
+                g: component(&hex[1..2])? * 0x11,
+                b: component(&hex[2..3])? * 0x11,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The color `t` (from 0 to 1) of the way from `self` to `other`.
+    pub fn mix(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * t).round() as u8;
+        Rgb {
+            r: mix(self.r, other.r),
+            g: mix(self.g, other.g),
+            b: mix(self.b, other.b),
+        }
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
 impl Display for User<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.names.international)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn color(light: &str, dark: &str) -> Color {
+        Color {
+            light: light.into(),
+            dark: dark.into(),
+        }
+    }
+
+    #[test]
+    fn name_styles() {
+        assert_eq!(
+            Rgb::parse("#EE2222"),
+            Some(Rgb {
+                r: 0xee,
+                g: 0x22,
+                b: 0x22
+            })
+        );
+        assert_eq!(
+            Rgb::parse("0af").map(|rgb| rgb.to_string()),
+            Some("#00aaff".into())
+        );
+        assert_eq!(Rgb::parse("#12345"), None);
+        assert_eq!(Rgb::parse("#+1+2+3"), None);
+        assert_eq!(Rgb::parse("+12"), None);
+
+        let solid = NameStyle::Solid {
+            color: color("#000000", "#ffffff"),
+        };
+        assert_eq!(solid.ansi("ab", Theme::Light), "\x1b[38;2;0;0;0mab\x1b[0m");
+        assert_eq!(
+            solid.html("<a>", Theme::Dark),
+            "<span style=\"color: #ffffff\">&lt;a&gt;</span>"
+        );
+
+        let gradient = NameStyle::Gradient {
+            color_from: color("#000000", "#000000"),
+            color_to: color("#ffffff", "#ffffff"),
+        };
+        assert_eq!(
+            gradient.ansi("abc", Theme::Dark),
+            "\x1b[38;2;0;0;0ma\x1b[38;2;128;128;128mb\x1b[38;2;255;255;255mc\x1b[0m"
+        );
+        let invalid = NameStyle::Solid {
+            color: color("", ""),
+        };
+        assert_eq!(invalid.ansi("abc", Theme::Dark), "abc");
+    }
+}
@@ -55,6 +55,27 @@ pub struct Color {
     pub dark: String,
 }
 
+/// Variant of a [`Color`], matching the theme of the site.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Theme {
+    /// Colors for a light background.
+    Light,
+    /// Colors for a dark background.
+    #[default]
+    Dark,
+}
+
+/// A color as red, green and blue components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb {
+    /// Red component.
+    pub r: u8,
+    /// Green component.
+    pub g: u8,
+    /// Blue component.
+    pub b: u8,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub struct Location {
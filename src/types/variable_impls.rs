@@ -1,8 +1,13 @@
 use std::fmt::Display;
 
-use crate::api::variables::VariableId;
+use crate::api::{
+    self,
+    links::LinkEndpoint,
+    runs::ValueType,
+    variables::{ValueId, VariableId},
+};
 
-use super::{Value, Variable};
+use super::{link_impls::find_link, LinkRel, Value, Variable};
 
 impl<'a> From<Variable<'a>> for VariableId<'a> {
     fn from(value: Variable<'a>) -> Self {
@@ -10,6 +15,73 @@ impl<'a> From<Variable<'a>> for VariableId<'a> {
     }
 }
 
+/// Endpoint that retrieves the variable again.
+impl<'a> From<&Variable<'a>> for api::variables::Variable<'a> {
+    fn from(value: &Variable<'a>) -> Self {
+        Self::builder()
+            .id(value.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl<'a> Variable<'a> {
+    /// Find the `ID` of the pre-defined value with the given `label`.
+    ///
+    /// Labels are compared case-insensitively.
+    pub fn value_id(&self, label: &str) -> Option<&ValueId<'a>> {
+        self.values
+            .values
+            .iter()
+            .find(|(_, value)| value.label.eq_ignore_ascii_case(label))
+            .map(|(id, _)| id)
+    }
+
+    /// Pick the value with the given `label` for submitting a run.
+    ///
+    /// Returns a pre-defined value if one matches `label`, otherwise the label
+    /// itself as free text if the variable is user-defined.
+    pub fn value_for(&self, label: &str) -> Option<ValueType<'a>> {
+        match self.value_id(label) {
+            Some(id) => Some(ValueType::PreDefined { value: id.clone() }),
+            None if self.user_defined => Some(ValueType::UserDefined {
+                value: label.to_owned().into(),
+            }),
+            None => None,
+        }
+    }
+
+    /// The `value` of this variable stored in a run, as it is submitted.
+    ///
+    /// Free text of a user-defined variable is submitted as such, anything else
+    /// as a pre-defined value.
+    pub fn run_value(&self, value: &str) -> ValueType<'static> {
+        let id = ValueId::new(value.to_owned());
+        if self.user_defined && !self.values.values.contains_key(&id) {
+            ValueType::UserDefined {
+                value: value.to_owned().into(),
+            }
+        } else {
+            ValueType::PreDefined { value: id }
+        }
+    }
+
+    /// Endpoint for this variable.
+    pub fn self_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::SelfLink)
+    }
+
+    /// Endpoint for the game this variable belongs to.
+    pub fn game_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Game)
+    }
+
+    /// Endpoint for the category this variable applies to.
+    pub fn category_link(&self) -> Option<LinkEndpoint<'_>> {
+        find_link(&self.links, LinkRel::Category)
+    }
+}
+
 impl Display for Variable<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", &self.name)
@@ -0,0 +1,799 @@
+//! # Watch
+//!
+//! speedrun.com has no push API. A [`RunWatcher`] polls the runs endpoint at
+//! a fixed interval and turns the results into a stream of [`WatchEvent`]s:
+//! new submissions, verifications, rejections and new world records on
+//! watched boards.
+//!
+//! The position of the watcher (a high-water mark of the newest submission and
+//! verification seen) is kept in a [`StateStore`], so that a restarted watcher
+//! continues where it stopped instead of reporting old runs again. The first
+//! poll without a stored state only records the current position.
+//!
+//! A [`NotificationWatcher`] does the same for the notifications of the
+//! authenticated user, reporting every notification once.
+//!
+//! ```rust ,no_run
+//! use futures::TryStreamExt;
+//! use speedrun_api::{
+//!     watch::{FileStore, RunWatcher, WatchEvent},
+//!     SpeedrunApiBuilder,
+//! };
+//!
+//! # #[tokio::main]
+//! # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = SpeedrunApiBuilder::new().build_async()?;
+//! let watcher = RunWatcher::builder().game("v1pxjz68").build()?;
+//!
+//! let store = FileStore::new("watch.json");
+//! let mut events = Box::pin(watcher.stream_with(&client, store, tokio::time::sleep));
+//! while let Some(event) = events.try_next().await? {
+//!     if let WatchEvent::Verified(run) = event {
+//!         println!("verified: {}", run.weblink);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use futures::{stream, Stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    api::{
+        categories::CategoryId,
+        games::GameId,
+        runs::{RunId, RunStatus, Runs, RunsSorting},
+        ApiError, AsyncClient, AsyncQuery, Direction, PagedEndpointExt,
+    },
+    boards::{Board, BoardEndpoint},
+    types,
+};
+
+mod notifications;
+
+pub use notifications::{
+    NotificationEvent, NotificationKind, NotificationState, NotificationWatcher,
+    NotificationWatcherBuilder, NotificationWatcherBuilderError,
+};
+
+/// Errors that occur while watching.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum WatchError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Error from the API.
+    #[error("API error: {0}")]
+    Api(#[from] ApiError<E>),
+    /// The state could not be loaded or saved.
+    #[error("state store error: {0}")]
+    Store(#[from] io::Error),
+}
+
+/// An event reported by a [`RunWatcher`].
+#[derive(Debug, Clone)]
+pub enum WatchEvent {
+    /// A run was submitted.
+    Submitted(types::Run<'static>),
+    /// A run was verified.
+    Verified(types::Run<'static>),
+    /// A run was rejected.
+    Rejected(types::Run<'static>),
+    /// A newly verified run took first place on a watched board.
+    WorldRecord {
+        /// Name of the board.
+        board: String,
+        /// The record run.
+        run: types::Run<'static>,
+        /// `ID` of the previous record run, if there was one.
+        previous: Option<RunId<'static>>,
+    },
+}
+
+/// Polls the runs of a game or category and reports changes.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into, strip_option))]
+pub struct RunWatcher<'a> {
+    #[doc = r"Only watch runs of `game`."]
+    #[builder(default)]
+    game: Option<GameId<'a>>,
+    #[doc = r"Only watch runs of `category`."]
+    #[builder(default)]
+    category: Option<CategoryId<'a>>,
+    #[doc = r"Time between polls (default: 60s)."]
+    #[builder(default = "Duration::from_secs(60)")]
+    interval: Duration,
+    #[doc = r"Number of most recently rejected runs checked for new rejections (default: 50)."]
+    #[builder(default = "50")]
+    rejected_window: usize,
+    #[builder(setter(name = "_boards"), private, default)]
+    boards: Vec<(String, BoardEndpoint<'a>)>,
+}
+
+/// Position of a [`RunWatcher`], stored between polls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchState {
+    submitted: Mark,
+    verified: Mark,
+    rejected: VecDeque<RunId<'static>>,
+    records: BTreeMap<String, RunId<'static>>,
+}
+
+/// Newest timestamp seen, with the runs seen at exactly that time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Mark {
+    at: Option<String>,
+    runs: Vec<RunId<'static>>,
+}
+
+/// Storage for the state of a watcher.
+pub trait StateStore<S> {
+    /// The stored state, `None` if nothing was stored yet.
+    fn load(&self) -> io::Result<Option<S>>;
+
+    /// Replace the stored state with `state`.
+    fn save(&mut self, state: &S) -> io::Result<()>;
+}
+
+/// A [`StateStore`] kept in memory.
+#[derive(Debug, Clone)]
+pub struct MemoryStore<S> {
+    state: Option<S>,
+}
+
+/// A [`StateStore`] persisted to a JSON file.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl<'a> RunWatcher<'a> {
+    /// Create a builder for a run watcher.
+    pub fn builder() -> RunWatcherBuilder<'a> {
+        RunWatcherBuilder::default()
+    }
+
+    /// Poll using `client` until the stream is dropped, keeping the position
+    /// in `store`.
+    ///
+    /// The state is saved after every poll, before its events are yielded.
+    /// Errors are yielded as they occur; polling continues afterwards.
+    /// Requires the `tokio` feature, see [`RunWatcher::stream_with`] otherwise.
+    #[cfg(feature = "tokio")]
+    pub fn stream<'s, C, S>(
+        &'s self,
+        client: &'s C,
+        store: S,
+    ) -> impl Stream<Item = Result<WatchEvent, WatchError<C::Error>>> + 's
+    where
+        C: AsyncClient + Sync,
+        S: StateStore<WatchState> + 's,
+    {
+        self.stream_with(client, store, tokio::time::sleep)
+    }
+
+    /// Like [`RunWatcher::stream`], waiting between polls with `sleep`.
+    pub fn stream_with<'s, C, S, F, T>(
+        &'s self,
+        client: &'s C,
+        store: S,
+        sleep: F,
+    ) -> impl Stream<Item = Result<WatchEvent, WatchError<C::Error>>> + 's
+    where
+        C: AsyncClient + Sync,
+        S: StateStore<WatchState> + 's,
+        F: Fn(Duration) -> T + 's,
+        T: Future<Output = ()>,
+    {
+        watch(self, client, store, sleep)
+    }
+
+    /// Poll once, updating `state` and returning the events since the
+    /// previous poll in chronological order.
+    ///
+    /// When `state` is empty, only the current position is recorded. If a
+    /// request fails, `state` may already be partly updated and the events of
+    /// the poll are lost: poll a copy of the state and keep it only on
+    /// success, as the streams do.
+    pub async fn poll<C>(
+        &self,
+        client: &C,
+        state: &mut WatchState,
+    ) -> Result<Vec<WatchEvent>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let baseline = *state == WatchState::default();
+        let mut events = Vec::new();
+
+        let submitted = self.runs_endpoint(None, RunsSorting::Submitted);
+        let runs = newer_runs(client, &submitted, &mut state.submitted, baseline, |run| {
+            run.submitted.clone()
+        })
+        .await?;
+        events.extend(runs.into_iter().map(WatchEvent::Submitted));
+
+        let verified = self.runs_endpoint(Some(RunStatus::Verified), RunsSorting::VerifyDate);
+        let runs = newer_runs(
+            client,
+            &verified,
+            &mut state.verified,
+            baseline,
+            |run| match &run.status {
+                types::Status::Verified { verify_date, .. } => verify_date.clone(),
+                _ => None,
+            },
+        )
+        .await?;
+        let verified: Vec<RunId<'static>> = runs.iter().map(|run| run.id.clone()).collect();
+        events.extend(runs.into_iter().map(WatchEvent::Verified));
+
+        // Rejections are sorted by the time of the decision, so a run rejected
+        // long after its submission is still among the most recent ones.
+        let rejected = self.runs_endpoint(Some(RunStatus::Rejected), RunsSorting::VerifyDate);
+        let recent: Vec<types::Run<'static>> = rejected
+            .stream(client)
+            .take(self.rejected_window)
+            .try_collect()
+            .await?;
+        for run in recent.into_iter().rev() {
+            if state.rejected.contains(&run.id) {
+                continue;
+            }
+            state.rejected.push_back(run.id.clone());
+            if !baseline {
+                events.push(WatchEvent::Rejected(run));
+            }
+        }
+        while state.rejected.len() > self.rejected_window {
+            state.rejected.pop_front();
+        }
+
+        for (name, endpoint) in &self.boards {
+            if verified.is_empty() && state.records.contains_key(name) {
+                continue;
+            }
+            let leaderboard: types::Leaderboard<'static> = endpoint.query_async(client).await?;
+            let Some(record) = leaderboard.runs.into_iter().next() else {
+                continue;
+            };
+            // Boards seen for the first time only record their current record.
+            // A different record that was not verified since the previous poll
+            // replaces a deleted or rejected record and is not reported.
+            let previous = state.records.insert(name.clone(), record.run.id.clone());
+            if previous
+                .as_ref()
+                .is_some_and(|previous| *previous != record.run.id)
+                && verified.contains(&record.run.id)
+            {
+                events.push(WatchEvent::WorldRecord {
+                    board: name.clone(),
+                    run: record.run,
+                    previous,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn runs_endpoint(&self, status: Option<RunStatus>, orderby: RunsSorting) -> Runs<'a> {
+        let mut builder = Runs::builder();
+        builder.orderby(orderby).direction(Direction::Desc);
+        if let Some(game) = &self.game {
+            builder.game(game.clone());
+        }
+        if let Some(category) = &self.category {
+            builder.category(category.clone());
+        }
+        if let Some(status) = status {
+            builder.status(status);
+        }
+        builder.build().expect("all required fields are set")
+    }
+}
+
+#[async_trait]
+impl<C> Poll<C> for RunWatcher<'_>
+where
+    C: AsyncClient + Sync,
+{
+    type State = WatchState;
+    type Event = WatchEvent;
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(
+        &self,
+        client: &C,
+        state: &mut WatchState,
+    ) -> Result<Vec<WatchEvent>, ApiError<C::Error>> {
+        RunWatcher::poll(self, client, state).await
+    }
+}
+
+impl<'a> RunWatcherBuilder<'a> {
+    /// Report new world records on the board retrieved by `endpoint`, using
+    /// `name` in the events.
+    pub fn board<N>(&mut self, name: N, endpoint: BoardEndpoint<'a>) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.boards
+            .get_or_insert_with(Vec::new)
+            .push((name.into(), endpoint));
+        self
+    }
+
+    /// Report new world records on multiple boards, named by their label.
+    pub fn boards<I>(&mut self, iter: I) -> &mut Self
+    where
+        I: IntoIterator<Item = Board<'a>>,
+    {
+        self.boards
+            .get_or_insert_with(Vec::new)
+            .extend(iter.into_iter().map(|board| (board.label, board.endpoint)));
+        self
+    }
+}
+
+impl<S> MemoryStore<S> {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Self { state: None }
+    }
+}
+
+impl<S> Default for MemoryStore<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Clone> StateStore<S> for MemoryStore<S> {
+    fn load(&self) -> io::Result<Option<S>> {
+        Ok(self.state.clone())
+    }
+
+    fn save(&mut self, state: &S) -> io::Result<()> {
+        self.state = Some(state.clone());
+        Ok(())
+    }
+}
+
+impl FileStore {
+    /// Create a store at `path`. The file is created on the first save.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+        }
+    }
+}
+
+impl<S> StateStore<S> for FileStore
+where
+    S: Serialize + DeserializeOwned,
+{
+    fn load(&self) -> io::Result<Option<S>> {
+        match fs::read(&self.path) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save(&mut self, state: &S) -> io::Result<()> {
+        // Write to a temporary file first so that a crash never leaves a
+        // truncated state behind.
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(state)?)?;
+        fs::rename(tmp, &self.path)
+    }
+}
+
+/// A watcher polled by [`watch`].
+#[async_trait]
+trait Poll<C>: Sync
+where
+    C: AsyncClient + Sync,
+{
+    /// Position of the watcher, stored between polls.
+    type State: Clone + Default + Send;
+    /// Events reported by the watcher.
+    type Event: Send;
+
+    /// Time between polls.
+    fn interval(&self) -> Duration;
+
+    /// Poll once, updating `state` and returning the new events.
+    async fn poll(
+        &self,
+        client: &C,
+        state: &mut Self::State,
+    ) -> Result<Vec<Self::Event>, ApiError<C::Error>>;
+}
+
+/// Poll `watcher` using `client` until the stream is dropped, keeping the
+/// position in `store` and waiting between polls with `sleep`.
+fn watch<'s, W, C, S, F, T>(
+    watcher: &'s W,
+    client: &'s C,
+    store: S,
+    sleep: F,
+) -> impl Stream<Item = Result<W::Event, WatchError<C::Error>>> + 's
+where
+    W: Poll<C>,
+    C: AsyncClient + Sync,
+    S: StateStore<W::State> + 's,
+    F: Fn(Duration) -> T + 's,
+    T: Future<Output = ()>,
+{
+    stream::unfold(
+        (Context::new(store), sleep),
+        move |(mut context, sleep)| async move {
+            loop {
+                if let Some(event) = context.pending.pop_front() {
+                    return Some((Ok(event), (context, sleep)));
+                }
+                if !std::mem::take(&mut context.first) {
+                    sleep(watcher.interval()).await;
+                }
+                if let Err(err) = context.poll(watcher, client).await {
+                    return Some((Err(err), (context, sleep)));
+                }
+            }
+        },
+    )
+}
+
+/// State of a watcher stream between polls.
+struct Context<S, T, E> {
+    store: S,
+    state: Option<T>,
+    pending: VecDeque<E>,
+    first: bool,
+}
+
+impl<S, T, E> Context<S, T, E> {
+    fn new(store: S) -> Self {
+        Self {
+            store,
+            state: None,
+            pending: VecDeque::new(),
+            first: true,
+        }
+    }
+
+    /// Poll `watcher` once, saving the state before queueing the events.
+    ///
+    /// The poll works on a copy of the state, which replaces the state only
+    /// if the poll succeeds, so that a failed poll is retried from the same
+    /// position.
+    async fn poll<W, C>(&mut self, watcher: &W, client: &C) -> Result<(), WatchError<C::Error>>
+    where
+        W: Poll<C, State = T, Event = E>,
+        C: AsyncClient + Sync,
+        S: StateStore<T>,
+        T: Clone + Default,
+    {
+        let mut state = match &self.state {
+            Some(state) => state.clone(),
+            None => self.store.load()?.unwrap_or_default(),
+        };
+        let events = watcher.poll(client, &mut state).await?;
+        self.store.save(&state)?;
+        self.state = Some(state);
+        self.pending.extend(events);
+        Ok(())
+    }
+}
+
+/// Retrieves the runs returned by `endpoint` (sorted newest first) that are
+/// newer than `mark`, oldest first, and advances `mark`.
+///
+/// With `baseline` set, only `mark` is updated.
+async fn newer_runs<C, F>(
+    client: &C,
+    endpoint: &Runs<'_>,
+    mark: &mut Mark,
+    baseline: bool,
+    timestamp: F,
+) -> Result<Vec<types::Run<'static>>, ApiError<C::Error>>
+where
+    C: AsyncClient + Sync,
+    F: Fn(&types::Run<'static>) -> Option<String>,
+{
+    let baseline = baseline || mark.at.is_none();
+    let mut runs = Vec::new();
+    let mut newest = Mark::default();
+    let mut results = endpoint.stream::<types::Run<'static>, _>(client);
+    while let Some(run) = results.try_next().await? {
+        let Some(at) = timestamp(&run) else {
+            continue;
+        };
+        if let Some(previous) = &mark.at {
+            if at < *previous {
+                break;
+            }
+            if at == *previous && mark.runs.contains(&run.id) {
+                continue;
+            }
+        }
+        if *newest.at.get_or_insert_with(|| at.clone()) == at {
+            newest.runs.push(run.id.clone());
+        } else if baseline {
+            break;
+        }
+        if !baseline {
+            runs.push(run);
+        }
+    }
+
+    if let Some(at) = newest.at {
+        if mark.at.as_ref() == Some(&at) {
+            mark.runs.extend(newest.runs);
+        } else {
+            *mark = Mark {
+                at: Some(at),
+                runs: newest.runs,
+            };
+        }
+    }
+    runs.reverse();
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use futures::executor::block_on;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        api::leaderboards::FullGameLeaderboard,
+        fixtures,
+        mock::{self, MockClient},
+    };
+
+    /// Runs known to the API, newest first for each ordering.
+    #[derive(Default)]
+    struct Site {
+        submitted: Vec<Value>,
+        verified: Vec<Value>,
+        rejected: Vec<Value>,
+        record: Option<Value>,
+        /// Fail the requests for rejected runs.
+        unavailable: bool,
+    }
+
+    impl Site {
+        fn submit(&mut self, id: &str, at: &str) {
+            let mut run = fixtures::run_json(id, "p", 60.0, "2021-01-01");
+            run["status"] = json!({ "status": "new" });
+            run["submitted"] = json!(at);
+            self.submitted.insert(0, run);
+        }
+
+        fn verify(&mut self, id: &str, at: &str) {
+            let mut run = self.find(id);
+            run["status"] = json!({ "status": "verified", "examiner": "mod", "verify-date": at });
+            self.verified.insert(0, run);
+        }
+
+        fn reject(&mut self, id: &str) {
+            let mut run = self.find(id);
+            run["status"] = json!({ "status": "rejected", "examiner": "mod", "reason": "" });
+            self.rejected.insert(0, run);
+        }
+
+        fn find(&self, id: &str) -> Value {
+            self.submitted
+                .iter()
+                .find(|run| run["id"] == id)
+                .cloned()
+                .unwrap()
+        }
+    }
+
+    fn client(site: &Arc<Mutex<Site>>) -> MockClient {
+        let site = Arc::clone(site);
+        MockClient::new(move |request| {
+            let site = site.lock().unwrap();
+            let runs = match (request.path(), request.query("status").as_deref()) {
+                ("runs", None) => &site.submitted,
+                ("runs", Some("verified")) => &site.verified,
+                ("runs", Some("rejected")) if !site.unavailable => &site.rejected,
+                ("leaderboards/g/category/c", _) => {
+                    let runs: Vec<_> = site
+                        .record
+                        .iter()
+                        .map(|run| json!({ "place": 1, "run": run }))
+                        .collect();
+                    return Some(json!({
+                        "weblink": "",
+                        "game": "g",
+                        "category": "c",
+                        "video-only": false,
+                        "runs": runs,
+                        "links": [],
+                    }));
+                }
+                _ => return None,
+            };
+            Some(mock::page(request, runs))
+        })
+    }
+
+    fn describe(events: &[WatchEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|event| match event {
+                WatchEvent::Submitted(run) => format!("submitted {}", run.id),
+                WatchEvent::Verified(run) => format!("verified {}", run.id),
+                WatchEvent::Rejected(run) => format!("rejected {}", run.id),
+                WatchEvent::WorldRecord { run, previous, .. } => {
+                    format!("record {} after {:?}", run.id, previous)
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn newer_runs_high_water_mark() {
+        let site = Arc::new(Mutex::new(Site::default()));
+        let client = client(&site);
+        let watcher = RunWatcher::builder().game("g").build().unwrap();
+        let endpoint = watcher.runs_endpoint(None, RunsSorting::Submitted);
+        let mut mark = Mark::default();
+        let newer = |mark: &mut Mark, baseline: bool| -> Vec<String> {
+            block_on(newer_runs(&client, &endpoint, mark, baseline, |run| {
+                run.submitted.clone()
+            }))
+            .unwrap()
+            .iter()
+            .map(|run| run.id.to_string())
+            .collect()
+        };
+
+        site.lock().unwrap().submit("a", "2021-01-01T00:00:01Z");
+        site.lock().unwrap().submit("b", "2021-01-01T00:00:02Z");
+        assert!(newer(&mut mark, true).is_empty());
+        assert_eq!(mark.at.as_deref(), Some("2021-01-01T00:00:02Z"));
+        assert_eq!(mark.runs, [RunId::new("b")]);
+
+        // A run submitted at the time of the mark is still new.
+        site.lock().unwrap().submit("c", "2021-01-01T00:00:02Z");
+        site.lock().unwrap().submit("d", "2021-01-01T00:00:03Z");
+        assert_eq!(newer(&mut mark, false), ["c", "d"]);
+        assert_eq!(mark.at.as_deref(), Some("2021-01-01T00:00:03Z"));
+        assert_eq!(mark.runs, [RunId::new("d")]);
+        assert!(newer(&mut mark, false).is_empty());
+    }
+
+    #[test]
+    fn poll_reports_changes() {
+        let site = Arc::new(Mutex::new(Site::default()));
+        let client = client(&site);
+        let board = FullGameLeaderboard::builder()
+            .game("g")
+            .category("c")
+            .build()
+            .unwrap();
+        let watcher = RunWatcher::builder()
+            .game("g")
+            .rejected_window(2_usize)
+            .board("Any%", BoardEndpoint::FullGame(board))
+            .build()
+            .unwrap();
+        let mut state = WatchState::default();
+        let poll =
+            |state: &mut WatchState| describe(&block_on(watcher.poll(&client, state)).unwrap());
+
+        {
+            let mut site = site.lock().unwrap();
+            site.submit("a", "2021-01-01T00:00:01Z");
+            site.verify("a", "2021-01-02T00:00:00Z");
+            site.submit("x", "2021-01-01T00:00:02Z");
+            site.reject("x");
+            site.record = Some(site.verified[0].clone());
+        }
+        // The first poll only records the current position.
+        assert!(poll(&mut state).is_empty());
+        assert_eq!(state.rejected, [RunId::new("x")]);
+        assert_eq!(state.records["Any%"], RunId::new("a"));
+
+        {
+            let mut site = site.lock().unwrap();
+            site.submit("b", "2021-01-02T00:00:01Z");
+            site.submit("c", "2021-01-02T00:00:02Z");
+            site.verify("b", "2021-01-03T00:00:00Z");
+            site.submit("y", "2021-01-02T00:00:03Z");
+            site.reject("y");
+            site.reject("c");
+            site.record = Some(site.verified[0].clone());
+        }
+        assert_eq!(
+            poll(&mut state),
+            [
+                "submitted b",
+                "submitted c",
+                "submitted y",
+                "verified b",
+                "rejected y",
+                "rejected c",
+                "record b after Some(RunId(\"a\"))",
+            ]
+        );
+        assert_eq!(state.rejected, [RunId::new("y"), RunId::new("c")]);
+
+        // The record run is deleted: the previous record is back in first
+        // place, which is not a new record.
+        {
+            let mut site = site.lock().unwrap();
+            site.submit("d", "2021-01-03T00:00:01Z");
+            site.verify("d", "2021-01-04T00:00:00Z");
+            site.record = Some(site.verified[2].clone());
+        }
+        assert_eq!(poll(&mut state), ["submitted d", "verified d"]);
+        assert_eq!(state.records["Any%"], RunId::new("a"));
+    }
+
+    #[test]
+    fn failed_poll_keeps_the_state() {
+        let site = Arc::new(Mutex::new(Site::default()));
+        let client = client(&site);
+        let watcher = RunWatcher::builder().game("g").build().unwrap();
+        let mut context = Context::new(MemoryStore::new());
+
+        site.lock().unwrap().submit("a", "2021-01-01T00:00:01Z");
+        block_on(context.poll(&watcher, &client)).unwrap();
+        let baseline = context.state.clone();
+
+        // Requesting the submissions succeeds, the rejections fail.
+        {
+            let mut site = site.lock().unwrap();
+            site.submit("b", "2021-01-01T00:00:02Z");
+            site.unavailable = true;
+        }
+        assert!(block_on(context.poll(&watcher, &client)).is_err());
+        assert_eq!(context.state, baseline);
+        assert_eq!(context.store.load().unwrap(), baseline);
+        assert!(context.pending.is_empty());
+
+        site.lock().unwrap().unavailable = false;
+        block_on(context.poll(&watcher, &client)).unwrap();
+        assert_eq!(describe(context.pending.make_contiguous()), ["submitted b"]);
+    }
+
+    #[test]
+    fn file_store_round_trip() {
+        let path = std::env::temp_dir().join(format!("watch-{}.json", std::process::id()));
+        let mut store = FileStore::new(&path);
+        assert_eq!(StateStore::<WatchState>::load(&store).unwrap(), None);
+
+        let mut state = WatchState::default();
+        state.submitted.at = Some("2024-01-02T03:04:05Z".into());
+        state.submitted.runs.push(RunId::new("abc"));
+        state.records.insert("Any%".into(), RunId::new("def"));
+        store.save(&state).unwrap();
+        assert_eq!(store.load().unwrap(), Some(state));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,345 @@
+use std::{collections::VecDeque, future::Future, time::Duration};
+
+use async_trait::async_trait;
+use futures::{Stream, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    api::{
+        notifications::{Notifications, NotificationsSorting},
+        runs::{Run, RunId},
+        ApiError, AsyncClient, Direction, PagedEndpointExt,
+    },
+    types::{self, Item, ReadStatus},
+    weblink::WebLink,
+};
+
+use super::{watch, Poll, StateStore, WatchError};
+
+/// What a notification is about.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationKind {
+    /// A forum post.
+    Post,
+    /// A run. `None` if the run `ID` could not be determined from the link.
+    Run(Option<RunId<'static>>),
+    /// A game.
+    Game,
+    /// A guide.
+    Guide,
+}
+
+/// A notification reported by a [`NotificationWatcher`].
+#[derive(Debug, Clone)]
+pub struct NotificationEvent {
+    /// The notification.
+    pub notification: types::Notification,
+    /// What the notification is about.
+    pub kind: NotificationKind,
+}
+
+/// Polls the notifications of the authenticated user and reports each
+/// notification once.
+///
+/// Requires a client with an API key.
+#[derive(Debug, Builder, Clone)]
+#[builder(setter(into))]
+pub struct NotificationWatcher {
+    #[doc = r"Time between polls (default: 60s)."]
+    #[builder(default = "Duration::from_secs(60)")]
+    interval: Duration,
+    #[doc = r"When `true` the first poll without a stored state reports unread notifications, otherwise it only records the current position (default: `false`)."]
+    #[builder(default)]
+    unread_on_start: bool,
+    #[doc = r"Number of notification `ID`s remembered to avoid reporting a notification twice (default: 200)."]
+    #[builder(default = "200")]
+    remembered: usize,
+}
+
+/// Position of a [`NotificationWatcher`], stored between polls.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NotificationState {
+    started: bool,
+    newest: Option<String>,
+    seen: VecDeque<String>,
+}
+
+impl NotificationWatcher {
+    /// Create a builder for a notification watcher.
+    pub fn builder() -> NotificationWatcherBuilder {
+        NotificationWatcherBuilder::default()
+    }
+
+    /// Poll using `client` until the stream is dropped, keeping the position
+    /// in `store`.
+    ///
+    /// The state is saved after every poll, before its events are yielded.
+    /// Errors are yielded as they occur; polling continues afterwards.
+    /// Requires the `tokio` feature, see [`NotificationWatcher::stream_with`]
+    /// otherwise.
+    #[cfg(feature = "tokio")]
+    pub fn stream<'s, C, S>(
+        &'s self,
+        client: &'s C,
+        store: S,
+    ) -> impl Stream<Item = Result<NotificationEvent, WatchError<C::Error>>> + 's
+    where
+        C: AsyncClient + Sync,
+        S: StateStore<NotificationState> + 's,
+    {
+        self.stream_with(client, store, tokio::time::sleep)
+    }
+
+    /// Like [`NotificationWatcher::stream`], waiting between polls with
+    /// `sleep`.
+    pub fn stream_with<'s, C, S, F, T>(
+        &'s self,
+        client: &'s C,
+        store: S,
+        sleep: F,
+    ) -> impl Stream<Item = Result<NotificationEvent, WatchError<C::Error>>> + 's
+    where
+        C: AsyncClient + Sync,
+        S: StateStore<NotificationState> + 's,
+        F: Fn(Duration) -> T + 's,
+        T: Future<Output = ()>,
+    {
+        watch(self, client, store, sleep)
+    }
+
+    /// Poll once, updating `state` and returning the notifications not seen
+    /// before, oldest first.
+    pub async fn poll<C>(
+        &self,
+        client: &C,
+        state: &mut NotificationState,
+    ) -> Result<Vec<NotificationEvent>, ApiError<C::Error>>
+    where
+        C: AsyncClient + Sync,
+    {
+        let baseline = !state.started;
+        let endpoint = Notifications::builder()
+            .orderby(NotificationsSorting::Created)
+            .direction(Direction::Desc)
+            .build()
+            .expect("all required fields are set");
+
+        let mut fresh = Vec::new();
+        let mut notifications = endpoint.stream::<types::Notification, _>(client);
+        while let Some(notification) = notifications.try_next().await? {
+            if state
+                .newest
+                .as_ref()
+                .is_some_and(|newest| notification.created < *newest)
+            {
+                break;
+            }
+            if state.seen.contains(&notification.id) {
+                continue;
+            }
+            fresh.push(notification);
+            if baseline && fresh.len() >= self.remembered {
+                break;
+            }
+        }
+        fresh.reverse();
+
+        for notification in &fresh {
+            state.seen.push_back(notification.id.clone());
+            if state.newest.as_ref() < Some(&notification.created) {
+                state.newest = Some(notification.created.clone());
+            }
+        }
+        while state.seen.len() > self.remembered.max(1) {
+            state.seen.pop_front();
+        }
+        state.started = true;
+
+        Ok(fresh
+            .into_iter()
+            .filter(|notification| {
+                !baseline || (self.unread_on_start && notification.status == ReadStatus::Unread)
+            })
+            .map(NotificationEvent::from)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<C> Poll<C> for NotificationWatcher
+where
+    C: AsyncClient + Sync,
+{
+    type State = NotificationState;
+    type Event = NotificationEvent;
+
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    async fn poll(
+        &self,
+        client: &C,
+        state: &mut NotificationState,
+    ) -> Result<Vec<NotificationEvent>, ApiError<C::Error>> {
+        NotificationWatcher::poll(self, client, state).await
+    }
+}
+
+impl NotificationKind {
+    /// Classify `item`, resolving the `ID` of runs.
+    pub fn of(item: &Item) -> Self {
+        match item {
+            Item::Post(_) => NotificationKind::Post,
+            Item::Run(uri) => NotificationKind::Run(run_id(uri)),
+            Item::Game(_) => NotificationKind::Game,
+            Item::Guide(_) => NotificationKind::Guide,
+        }
+    }
+}
+
+impl NotificationEvent {
+    /// Endpoint retrieving the run, for notifications about runs.
+    pub fn run_endpoint(&self) -> Option<Run<'static>> {
+        match &self.kind {
+            NotificationKind::Run(Some(id)) => Some(
+                Run::builder()
+                    .id(id.clone())
+                    .build()
+                    .expect("all required fields are set"),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl From<types::Notification> for NotificationEvent {
+    fn from(notification: types::Notification) -> Self {
+        Self {
+            kind: NotificationKind::of(&notification.item),
+            notification,
+        }
+    }
+}
+
+/// `ID` of the run linked by `uri`, either a page on speedrun.com or an API
+/// link.
+fn run_id(uri: &str) -> Option<RunId<'static>> {
+    if let Ok(WebLink::Run(run)) = WebLink::parse(uri) {
+        return Some(run.id);
+    }
+    let url = Url::parse(uri).ok()?;
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match segments.as_slice() {
+        [.., "runs", id] => Some(RunId::new(id.to_string())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{Arc, Mutex};
+
+    use futures::executor::block_on;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::mock::{self, MockClient};
+
+    /// A client serving `notifications`, newest first.
+    fn client(notifications: &Arc<Mutex<Vec<Value>>>) -> MockClient {
+        let notifications = Arc::clone(notifications);
+        MockClient::new(move |request| match request.path() {
+            "notifications" => Some(mock::page(request, &notifications.lock().unwrap())),
+            _ => None,
+        })
+    }
+
+    fn notification(id: &str, created: &str, status: &str) -> Value {
+        json!({
+            "id": id,
+            "created": created,
+            "status": status,
+            "text": "",
+            "item": { "rel": "post", "uri": "https://www.speedrun.com/forum" },
+            "links": [],
+        })
+    }
+
+    fn poll(
+        watcher: &NotificationWatcher,
+        client: &MockClient,
+        state: &mut NotificationState,
+    ) -> Vec<String> {
+        block_on(watcher.poll(client, state))
+            .unwrap()
+            .into_iter()
+            .map(|event| event.notification.id)
+            .collect()
+    }
+
+    #[test]
+    fn poll_reports_each_notification_once() {
+        let notifications = Arc::new(Mutex::new(vec![
+            notification("n2", "2021-01-01T00:00:02Z", "unread"),
+            notification("n1", "2021-01-01T00:00:01Z", "read"),
+        ]));
+        let client = client(&notifications);
+        let watcher = NotificationWatcher::builder()
+            .remembered(2_usize)
+            .build()
+            .unwrap();
+
+        let mut state = NotificationState::default();
+        assert!(poll(&watcher, &client, &mut state).is_empty());
+        assert_eq!(state.newest.as_deref(), Some("2021-01-01T00:00:02Z"));
+
+        // A notification created at the time of the newest one is still new.
+        notifications.lock().unwrap().splice(
+            0..0,
+            [
+                notification("n4", "2021-01-01T00:00:03Z", "unread"),
+                notification("n3", "2021-01-01T00:00:02Z", "unread"),
+            ],
+        );
+        assert_eq!(poll(&watcher, &client, &mut state), ["n3", "n4"]);
+        assert_eq!(state.seen, ["n3", "n4"]);
+        assert!(poll(&watcher, &client, &mut state).is_empty());
+    }
+
+    #[test]
+    fn baseline_reports_unread() {
+        let notifications = Arc::new(Mutex::new(vec![
+            notification("n2", "2021-01-01T00:00:02Z", "unread"),
+            notification("n1", "2021-01-01T00:00:01Z", "read"),
+        ]));
+        let client = client(&notifications);
+        let watcher = NotificationWatcher::builder()
+            .unread_on_start(true)
+            .build()
+            .unwrap();
+
+        let mut state = NotificationState::default();
+        assert_eq!(poll(&watcher, &client, &mut state), ["n2"]);
+        assert!(poll(&watcher, &client, &mut state).is_empty());
+    }
+
+    #[test]
+    fn classify_items() {
+        assert_eq!(
+            NotificationKind::of(&Item::Run("https://www.speedrun.com/smw/run/abc123".into())),
+            NotificationKind::Run(Some(RunId::new("abc123")))
+        );
+        assert_eq!(
+            NotificationKind::of(&Item::Run(
+                "https://www.speedrun.com/api/v1/runs/xyz789".into()
+            )),
+            NotificationKind::Run(Some(RunId::new("xyz789")))
+        );
+        assert_eq!(
+            NotificationKind::of(&Item::Guide("https://www.speedrun.com/smw/guide/1".into())),
+            NotificationKind::Guide
+        );
+    }
+}
@@ -0,0 +1,406 @@
+//! # Weblinks
+//!
+//! Parse speedrun.com web URLs (the links users share, and the `weblink` field
+//! on most resources) into typed IDs and endpoints, and build weblinks from
+//! IDs.
+//!
+//! ```rust
+//! use speedrun_api::weblink::WebLink;
+//!
+//! let link: WebLink<'_> = "https://www.speedrun.com/sms/run/90y6pm7e".parse().unwrap();
+//! match link {
+//!     WebLink::Run(run) => assert_eq!(run.id.to_string(), "90y6pm7e"),
+//!     _ => unreachable!(),
+//! }
+//! ```
+use std::{fmt::Display, str::FromStr};
+
+use thiserror::Error;
+use url::Url;
+
+use crate::api::{
+    categories::CategoryId,
+    games::GameId,
+    leaderboards::{FullGameLeaderboard, IndividualLevelLeaderboard},
+    levels::LevelId,
+    runs::{Run, RunId},
+    users::UserId,
+    variables::{ValueId, VariableId},
+};
+
+const SPEEDRUN_WEB_BASE_URL: &str = "https://www.speedrun.com/";
+
+/// Errors that occur when parsing a weblink.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum WebLinkError {
+    /// The URL failed to parse.
+    #[error("url parse error: {0}")]
+    Parse(#[from] url::ParseError),
+    /// The URL does not point to speedrun.com.
+    #[error("not a speedrun.com URL: {0}")]
+    ForeignHost(Url),
+    /// The URL points to a speedrun.com page that is not supported.
+    #[error("unrecognized speedrun.com URL: {0}")]
+    Unrecognized(Url),
+    /// The leaderboard selection (`x` query parameter) is malformed.
+    #[error("malformed leaderboard selection `{0}`")]
+    Selection(String),
+}
+
+/// A parsed speedrun.com weblink.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WebLink<'a> {
+    /// A game page, without a specific leaderboard selected.
+    Game(GameId<'a>),
+    /// A leaderboard page.
+    Leaderboard(LeaderboardLink<'a>),
+    /// A single run.
+    Run(RunLink<'a>),
+    /// A user profile.
+    User(UserId<'a>),
+}
+
+/// A weblink to a single run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunLink<'a> {
+    /// Game `ID` or abbreviation, when present in the URL.
+    pub game: Option<GameId<'a>>,
+    /// `ID` of the run.
+    pub id: RunId<'a>,
+}
+
+/// A weblink to a full-game or individual-level leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeaderboardLink<'a> {
+    /// Game `ID` or abbreviation.
+    pub game: GameId<'a>,
+    /// `ID` of the category.
+    pub category: CategoryId<'a>,
+    /// `ID` of the level for individual-level leaderboards.
+    pub level: Option<LevelId<'a>>,
+    /// Selected variable values, in the order they appear in the URL.
+    pub variables: Vec<(VariableId<'a>, ValueId<'a>)>,
+}
+
+impl WebLink<'static> {
+    /// Parse a speedrun.com weblink.
+    pub fn parse(input: &str) -> Result<Self, WebLinkError> {
+        Self::from_url(&Url::parse(input)?)
+    }
+
+    /// Parse an already parsed speedrun.com [`Url`].
+    pub fn from_url(url: &Url) -> Result<Self, WebLinkError> {
+        match url.host_str() {
+            Some("speedrun.com" | "www.speedrun.com") => {}
+            _ => return Err(WebLinkError::ForeignHost(url.clone())),
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+        let unrecognized = || WebLinkError::Unrecognized(url.clone());
+
+        match segments.as_slice() {
+            ["user" | "users", name, ..] => Ok(WebLink::User(UserId::new(name.to_string()))),
+            ["run" | "runs", id] => Ok(WebLink::Run(RunLink {
+                game: None,
+                id: RunId::new(id.to_string()),
+            })),
+            [game, "run" | "runs", id] => Ok(WebLink::Run(RunLink {
+                game: Some(GameId::new(game.to_string())),
+                id: RunId::new(id.to_string()),
+            })),
+            [game] | [game, "full_game" | "individual_levels"] if !is_reserved(game) => {
+                let game = GameId::new(game.to_string());
+                let selection = url
+                    .query_pairs()
+                    .find(|(key, _)| key == "x")
+                    .map(|(_, value)| value.into_owned());
+                match selection {
+                    Some(selection) => {
+                        LeaderboardLink::from_selection(game, &selection).map(WebLink::Leaderboard)
+                    }
+                    None => Ok(WebLink::Game(game)),
+                }
+            }
+            _ => Err(unrecognized()),
+        }
+    }
+}
+
+impl<'a> WebLink<'a> {
+    /// Build the speedrun.com URL for this weblink.
+    pub fn url(&self) -> Url {
+        let base = Url::parse(SPEEDRUN_WEB_BASE_URL).expect("valid base URL");
+        base.join(&self.path())
+            .expect("weblink path is always a valid relative URL")
+    }
+
+    /// Game `ID` or abbreviation this weblink belongs to, if known.
+    pub fn game(&self) -> Option<&GameId<'a>> {
+        match self {
+            WebLink::Game(game) => Some(game),
+            WebLink::Leaderboard(board) => Some(&board.game),
+            WebLink::Run(run) => run.game.as_ref(),
+            WebLink::User(_) => None,
+        }
+    }
+
+    fn path(&self) -> String {
+        match self {
+            WebLink::Game(game) => game.to_string(),
+            WebLink::Leaderboard(board) => format!("{}?x={}", board.game, board.selection()),
+            WebLink::Run(RunLink {
+                game: Some(game),
+                id,
+            }) => format!("{game}/run/{id}"),
+            WebLink::Run(RunLink { game: None, id }) => format!("run/{id}"),
+            WebLink::User(user) => format!("user/{user}"),
+        }
+    }
+}
+
+impl<'a> RunLink<'a> {
+    /// Create an endpoint that retrieves the linked run.
+    pub fn endpoint(&self) -> Run<'a> {
+        Run::builder()
+            .id(self.id.clone())
+            .build()
+            .expect("all required fields are set")
+    }
+}
+
+impl<'a> LeaderboardLink<'a> {
+    fn from_selection(game: GameId<'a>, selection: &str) -> Result<Self, WebLinkError> {
+        let malformed = || WebLinkError::Selection(selection.to_string());
+
+        let (level, rest) = match selection.strip_prefix("l_") {
+            Some(rest) => {
+                let (level, rest) = rest.split_once('-').ok_or_else(malformed)?;
+                (Some(LevelId::new(level.to_string())), rest)
+            }
+            None => (None, selection),
+        };
+
+        let mut parts = rest.split('-');
+        let category = match parts.next() {
+            Some(category) if !category.is_empty() => CategoryId::new(category.to_string()),
+            _ => return Err(malformed()),
+        };
+        let variables = parts
+            .map(|part| match part.split_once('.') {
+                Some((var, val)) if !var.is_empty() && !val.is_empty() => Ok((
+                    VariableId::new(var.to_string()),
+                    ValueId::new(val.to_string()),
+                )),
+                _ => Err(malformed()),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            game,
+            category,
+            level,
+            variables,
+        })
+    }
+
+    fn selection(&self) -> String {
+        let mut out = match &self.level {
+            Some(level) => format!("l_{}-{}", level, self.category),
+            None => self.category.to_string(),
+        };
+        for (var, val) in &self.variables {
+            out.push_str(&format!("-{var}.{val}"));
+        }
+        out
+    }
+
+    /// Create an endpoint that retrieves the linked full-game leaderboard.
+    ///
+    /// Returns `None` if this is an individual-level leaderboard.
+    pub fn full_game_leaderboard(&self) -> Option<FullGameLeaderboard<'a>> {
+        if self.level.is_some() {
+            return None;
+        }
+        let endpoint = FullGameLeaderboard::builder()
+            .game(self.game.clone())
+            .category(self.category.clone())
+            .variables(self.variables.iter().cloned())
+            .build()
+            .expect("all required fields are set");
+        Some(endpoint)
+    }
+
+    /// Create an endpoint that retrieves the linked individual-level
+    /// leaderboard.
+    ///
+    /// Returns `None` if this is a full-game leaderboard.
+    pub fn individual_level_leaderboard(&self) -> Option<IndividualLevelLeaderboard<'a>> {
+        let level = self.level.clone()?;
+        let endpoint = IndividualLevelLeaderboard::builder()
+            .game(self.game.clone())
+            .level(level)
+            .category(self.category.clone())
+            .variables(self.variables.iter().cloned())
+            .build()
+            .expect("all required fields are set");
+        Some(endpoint)
+    }
+}
+
+fn is_reserved(segment: &str) -> bool {
+    matches!(
+        segment,
+        "api"
+            | "games"
+            | "series"
+            | "user"
+            | "users"
+            | "run"
+            | "runs"
+            | "news"
+            | "forums"
+            | "threads"
+            | "guides"
+            | "resources"
+    )
+}
+
+impl FromStr for WebLink<'static> {
+    type Err = WebLinkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&Url> for WebLink<'static> {
+    type Error = WebLinkError;
+
+    fn try_from(value: &Url) -> Result<Self, Self::Error> {
+        Self::from_url(value)
+    }
+}
+
+impl Display for WebLink<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.url())
+    }
+}
+
+impl<'a> From<GameId<'a>> for WebLink<'a> {
+    fn from(value: GameId<'a>) -> Self {
+        WebLink::Game(value)
+    }
+}
+
+impl<'a> From<UserId<'a>> for WebLink<'a> {
+    fn from(value: UserId<'a>) -> Self {
+        WebLink::User(value)
+    }
+}
+
+impl<'a> From<RunLink<'a>> for WebLink<'a> {
+    fn from(value: RunLink<'a>) -> Self {
+        WebLink::Run(value)
+    }
+}
+
+impl<'a> From<LeaderboardLink<'a>> for WebLink<'a> {
+    fn from(value: LeaderboardLink<'a>) -> Self {
+        WebLink::Leaderboard(value)
+    }
+}
+
+impl<'a> From<RunLink<'a>> for RunId<'a> {
+    fn from(value: RunLink<'a>) -> Self {
+        value.id
+    }
+}
+
+impl<'a> From<LeaderboardLink<'a>> for CategoryId<'a> {
+    fn from(value: LeaderboardLink<'a>) -> Self {
+        value.category
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_run() {
+        let link = WebLink::parse("https://www.speedrun.com/sms/run/90y6pm7e").unwrap();
+        assert_eq!(
+            link,
+            WebLink::Run(RunLink {
+                game: Some(GameId::new("sms")),
+                id: RunId::new("90y6pm7e"),
+            })
+        );
+        assert_eq!(
+            link.to_string(),
+            "https://www.speedrun.com/sms/run/90y6pm7e"
+        );
+    }
+
+    #[test]
+    fn parse_user() {
+        let link = WebLink::parse("https://speedrun.com/user/Name/").unwrap();
+        assert_eq!(link, WebLink::User(UserId::new("Name")));
+        assert_eq!(link.to_string(), "https://www.speedrun.com/user/Name");
+    }
+
+    #[test]
+    fn parse_game() {
+        let link = WebLink::parse("https://www.speedrun.com/sms?h=Any").unwrap();
+        assert_eq!(link, WebLink::Game(GameId::new("sms")));
+    }
+
+    #[test]
+    fn parse_leaderboards() {
+        let link =
+            WebLink::parse("https://www.speedrun.com/sms?h=Any&x=n2y5jzd3-789x6p58.013v4y4q")
+                .unwrap();
+        let expected = LeaderboardLink {
+            game: GameId::new("sms"),
+            category: CategoryId::new("n2y5jzd3"),
+            level: None,
+            variables: vec![(VariableId::new("789x6p58"), ValueId::new("013v4y4q"))],
+        };
+        assert_eq!(link, WebLink::Leaderboard(expected.clone()));
+        assert!(expected.full_game_leaderboard().is_some());
+        assert!(expected.individual_level_leaderboard().is_none());
+        assert_eq!(
+            link.to_string(),
+            "https://www.speedrun.com/sms?x=n2y5jzd3-789x6p58.013v4y4q"
+        );
+
+        let link = WebLink::parse("https://www.speedrun.com/sms?x=l_29vjx29l-n2y5jzd3").unwrap();
+        let WebLink::Leaderboard(board) = link else {
+            panic!("expected a leaderboard link");
+        };
+        assert_eq!(board.level, Some(LevelId::new("29vjx29l")));
+        assert!(board.individual_level_leaderboard().is_some());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            WebLink::parse("https://example.com/sms"),
+            Err(WebLinkError::ForeignHost(_))
+        ));
+        assert!(matches!(
+            WebLink::parse("https://www.speedrun.com/sms?x=n2y5jzd3-789x6p58"),
+            Err(WebLinkError::Selection(_))
+        ));
+        assert!(matches!(
+            WebLink::parse("https://www.speedrun.com/"),
+            Err(WebLinkError::Unrecognized(_))
+        ));
+    }
+}